@@ -7,17 +7,191 @@ use pink_extension as pink;
 #[pink(inner=ink::contract)]
 mod {{contract_name}} {
     use super::pink;
-    use alloc::string::String;
+    use alloc::format;
+    use alloc::string::{String, ToString};
     use alloc::vec::Vec;
-    use pink::{http_get, http_post, PinkEnvironment};
+    use core::net::{Ipv4Addr, Ipv6Addr};
+    use pink::chain_extension::HttpResponse;
+    use pink::{http_get, http_post, http_req, PinkEnvironment};
+    use scale::{Decode, Encode};
+    use serde::{Deserialize, Serialize};
 
+    /// Size limit of the JSON request bodies.
+    const MAX_REQUEST_BODY: usize = 1024;
+
+    /// Default size limit of the responses returned by `proxy`.
+    const DEFAULT_MAX_PROXY_RESPONSE: u32 = 64 * 1024;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        NoPermissions,
+        /// The request body could not be serialized.
+        InvalidRequestBody,
+        /// 400 or 422: the API rejected the request.
+        BadRequest,
+        /// 401 or 403: the credentials are missing or invalid.
+        Unauthorized,
+        /// 404
+        NotFound,
+        /// 429: wait before sending more requests.
+        RateLimited,
+        /// 408, 504, or 524 which the Phala Worker answers with when a request takes too long.
+        Timeout,
+        /// Any other 5xx status.
+        ServerFailure(u16),
+        /// Any other status which is not 2xx.
+        UnexpectedStatus(u16),
+        InvalidResponseBody,
+        /// The origin can't be added to the proxy allowlist.
+        InvalidOrigin(ProxyError),
+    }
+
+    /// Why `proxy` refused or failed to fetch a URL.
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ProxyError {
+        /// The URL can't be parsed, or is ambiguous, e.g. it contains credentials.
+        InvalidUrl,
+        /// Only `https://` URLs are fetched.
+        NotHttps,
+        /// The origin is not in the allowlist, see `allow_proxy_origin`.
+        OriginNotAllowed,
+        /// The host is a loopback, private, link-local or otherwise non-public address.
+        PrivateAddress,
+        /// The response body exceeds the limit set by `set_max_proxy_response_size`.
+        ResponseTooLarge,
+        /// The response status is not 2xx.
+        Status(u16),
+    }
+
+    /// Type alias for the contract's result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// How requests to the API are authenticated.
+    #[derive(Clone, Default, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Auth {
+        #[default]
+        None,
+        /// `Authorization: Bearer <token>`
+        Bearer(String),
+        /// The key sent in a custom header, e.g. `X-API-Key`.
+        ApiKey { header: String, key: String },
+    }
+
+    /// Prints the kind of auth only, so the credentials don't end up in logs.
+    impl core::fmt::Debug for Auth {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Auth::None => f.write_str("None"),
+                Auth::Bearer(_) => f.write_str("Bearer(***)"),
+                Auth::ApiKey { header, .. } => write!(f, "ApiKey({header}: ***)"),
+            }
+        }
+    }
+
+    /// Where and how requests are sent.
+    #[derive(Debug, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Config {
+        /// Prepended to the path of each request, e.g. `https://api.example.com/v1`.
+        pub base_url: String,
+        /// Sent with every request, in addition to the JSON and auth headers.
+        pub headers: Vec<(String, String)>,
+        pub auth: Auth,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Todo {
+        pub id: u32,
+        pub title: String,
+        pub completed: bool,
+    }
+
+    #[derive(Serialize)]
+    struct NewTodo<'a> {
+        title: &'a str,
+        completed: bool,
+    }
+
+    /// The storage is encrypted, so the credentials in `config` stay private.
     #[ink(storage)]
-    pub struct {{ContractName}} {}
+    pub struct {{ContractName}} {
+        admin: AccountId,
+        config: Config,
+        /// Origins `proxy` may fetch from, in the form `https://host[:port]`.
+        proxy_origins: Vec<String>,
+        max_proxy_response_size: u32,
+    }
 
     impl {{ContractName}} {
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self {}
+            Self::new(Config {
+                base_url: String::from("https://jsonplaceholder.typicode.com"),
+                headers: Vec::new(),
+                auth: Auth::None,
+            })
+        }
+
+        #[ink(constructor)]
+        pub fn new(config: Config) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                config,
+                proxy_origins: Vec::new(),
+                max_proxy_response_size: DEFAULT_MAX_PROXY_RESPONSE,
+            }
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            if self.admin != self.env().caller() {
+                return Err(Error::NoPermissions);
+            }
+            Ok(())
+        }
+
+        /// Change the API configuration. Only the admin can do this.
+        #[ink(message)]
+        pub fn set_config(&mut self, config: Config) -> Result<()> {
+            self.ensure_admin()?;
+            self.config = config;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn base_url(&self) -> String {
+            self.config.base_url.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_todo(&self, id: u32) -> Result<Todo> {
+            let response = self.request("GET", &format!("todos/{id}"), Vec::new())?;
+            parse_json(&response)
+        }
+
+        #[ink(message)]
+        pub fn create_todo(&self, title: String) -> Result<Todo> {
+            let body = to_json(&NewTodo {
+                title: &title,
+                completed: false,
+            })?;
+            let response = self.request("POST", "todos", body)?;
+            parse_json(&response)
+        }
+
+        #[ink(message)]
+        pub fn delete_todo(&self, id: u32) -> Result<()> {
+            self.request("DELETE", &format!("todos/{id}"), Vec::new())?;
+            Ok(())
         }
 
         #[ink(message)]
@@ -32,30 +206,587 @@ mod {{contract_name}} {
             (response.status_code, response.body)
         }
 
+        /// Allow `proxy` to fetch from `origin`, e.g. `https://api.example.com`. Only the admin
+        /// can do this.
         #[ink(message)]
-        pub fn proxy(&self, url: String) -> (u16, Vec<u8>) {
+        pub fn allow_proxy_origin(&mut self, origin: String) -> Result<()> {
+            self.ensure_admin()?;
+            let origin = Origin::parse(&origin)
+                .and_then(|origin| origin.check_public())
+                .map_err(Error::InvalidOrigin)?
+                .to_string();
+            if !self.proxy_origins.contains(&origin) {
+                self.proxy_origins.push(origin);
+            }
+            Ok(())
+        }
+
+        /// Remove `origin` from the proxy allowlist. Only the admin can do this.
+        #[ink(message)]
+        pub fn remove_proxy_origin(&mut self, origin: String) -> Result<()> {
+            self.ensure_admin()?;
+            let origin = Origin::parse(&origin)
+                .map_err(Error::InvalidOrigin)?
+                .to_string();
+            self.proxy_origins.retain(|allowed| *allowed != origin);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn proxy_origins(&self) -> Vec<String> {
+            self.proxy_origins.clone()
+        }
+
+        /// Set the size limit of the responses returned by `proxy`. Only the admin can do this.
+        #[ink(message)]
+        pub fn set_max_proxy_response_size(&mut self, size: u32) -> Result<()> {
+            self.ensure_admin()?;
+            self.max_proxy_response_size = size;
+            Ok(())
+        }
+
+        /// Fetch `url` on behalf of the caller.
+        ///
+        /// Only HTTPS URLs of allowlisted origins are fetched, and never from non-public
+        /// addresses. Host names are resolved by the Phala Worker, so allowlist only hosts you
+        /// trust not to point to internal addresses.
+        #[ink(message)]
+        pub fn proxy(&self, url: String) -> core::result::Result<Vec<u8>, ProxyError> {
+            let origin = Origin::parse(&url)?.check_public()?.to_string();
+            if !self.proxy_origins.contains(&origin) {
+                return Err(ProxyError::OriginNotAllowed);
+            }
+
             let response = http_get!(&url);
-            (response.status_code, response.body)
+            if !(200..=299).contains(&response.status_code) {
+                return Err(ProxyError::Status(response.status_code));
+            }
+            if response.body.len() > self.max_proxy_response_size as usize {
+                return Err(ProxyError::ResponseTooLarge);
+            }
+            Ok(response.body)
+        }
+
+        /// Send a request to `path` relative to the base URL, with the configured headers.
+        fn request(&self, method: &str, path: &str, body: Vec<u8>) -> Result<HttpResponse> {
+            let url = format!(
+                "{}/{}",
+                self.config.base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            );
+            let mut headers = self.config.headers.clone();
+            headers.push(("Accept".into(), "application/json".into()));
+            if !body.is_empty() {
+                headers.push(("Content-Type".into(), "application/json".into()));
+            }
+            match &self.config.auth {
+                Auth::None => {}
+                Auth::Bearer(token) => {
+                    headers.push(("Authorization".into(), format!("Bearer {token}")))
+                }
+                Auth::ApiKey { header, key } => headers.push((header.clone(), key.clone())),
+            }
+
+            // the Phala Worker enforces the timeout, a request taking too long is answered
+            // with an error status instead of blocking the query
+            let response = http_req!(method, url, body, headers);
+            check_status(response.status_code)?;
+            Ok(response)
+        }
+    }
+
+    enum Host<'a> {
+        Domain(&'a str),
+        Ipv4(Ipv4Addr),
+        Ipv6(Ipv6Addr),
+    }
+
+    /// Host and port of a HTTPS URL, which `proxy` checks before fetching it.
+    struct Origin<'a> {
+        host: Host<'a>,
+        port: Option<u16>,
+    }
+
+    impl<'a> Origin<'a> {
+        /// Parse the origin of a HTTPS `url`, rejecting anything a HTTP client could read
+        /// differently.
+        fn parse(url: &'a str) -> core::result::Result<Self, ProxyError> {
+            let (scheme, rest) = url.split_once("://").ok_or(ProxyError::InvalidUrl)?;
+            if !scheme.eq_ignore_ascii_case("https") {
+                return Err(ProxyError::NotHttps);
+            }
+            let authority = rest.split(['/', '?', '#', '\\']).next().unwrap_or_default();
+            let (host, port) = match authority.strip_prefix('[') {
+                Some(ipv6) => {
+                    let (host, port) = ipv6.split_once(']').ok_or(ProxyError::InvalidUrl)?;
+                    let port = match port {
+                        "" => None,
+                        port => Some(port.strip_prefix(':').ok_or(ProxyError::InvalidUrl)?),
+                    };
+                    let ip = host.parse().or(Err(ProxyError::InvalidUrl))?;
+                    (Host::Ipv6(ip), port)
+                }
+                None => {
+                    let (host, port) = match authority.rsplit_once(':') {
+                        Some((host, port)) => (host, Some(port)),
+                        None => (authority, None),
+                    };
+                    // `@` (credentials), `%` (escapes) and the like are rejected here
+                    if host.is_empty()
+                        || !host
+                            .bytes()
+                            .all(|c| c.is_ascii_alphanumeric() || c == b'.' || c == b'-')
+                    {
+                        return Err(ProxyError::InvalidUrl);
+                    }
+                    // HTTP clients read hosts ending with a number as IPv4 address, also in
+                    // forms like `2130706433` or `0x7f.1`, so only dotted decimals are accepted
+                    let last_label = host.trim_end_matches('.').rsplit('.').next();
+                    let numeric = last_label.is_some_and(|label| {
+                        label.bytes().all(|c| c.is_ascii_digit())
+                            || label.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("0x"))
+                    });
+                    let host = if numeric {
+                        Host::Ipv4(host.parse().or(Err(ProxyError::InvalidUrl))?)
+                    } else {
+                        Host::Domain(host)
+                    };
+                    (host, port)
+                }
+            };
+            let port = match port {
+                Some(port) => Some(port.parse().or(Err(ProxyError::InvalidUrl))?),
+                None => None,
+            };
+            Ok(Self { host, port })
+        }
+
+        /// Reject non-public addresses.
+        fn check_public(self) -> core::result::Result<Self, ProxyError> {
+            let private = match &self.host {
+                Host::Domain(domain) => {
+                    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                    domain == "localhost" || domain.ends_with(".localhost")
+                }
+                Host::Ipv4(ip) => is_private_ipv4(ip),
+                Host::Ipv6(ip) => match embedded_ipv4(ip) {
+                    Some(ip) => is_private_ipv4(&ip),
+                    None => is_private_ipv6(ip),
+                },
+            };
+            if private {
+                return Err(ProxyError::PrivateAddress);
+            }
+            Ok(self)
+        }
+    }
+
+    /// The normalized form stored in the allowlist.
+    impl core::fmt::Display for Origin<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("https://")?;
+            match &self.host {
+                Host::Domain(domain) => {
+                    f.write_str(&domain.trim_end_matches('.').to_ascii_lowercase())?
+                }
+                Host::Ipv4(ip) => write!(f, "{ip}")?,
+                Host::Ipv6(ip) => write!(f, "[{ip}]")?,
+            }
+            match self.port {
+                None | Some(443) => Ok(()),
+                Some(port) => write!(f, ":{port}"),
+            }
+        }
+    }
+
+    fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+        let [a, b, ..] = ip.octets();
+        ip.is_private()
+            || ip.is_loopback()
+            || ip.is_link_local()
+            || ip.is_unspecified()
+            || ip.is_broadcast()
+            || ip.is_multicast()
+            || ip.is_documentation()
+            // 0.0.0.0/8 "this network" and 100.64.0.0/10 carrier-grade NAT
+            || a == 0
+            || (a == 100 && (b & 0xc0) == 64)
+    }
+
+    /// The IPv4 address carried by an IPv4-mapped (`::ffff:0:0/96`), IPv4-compatible
+    /// (`::/96`), NAT64 (`64:ff9b::/96`) or 6to4 (`2002::/16`) address, which reaches that
+    /// IPv4 host.
+    fn embedded_ipv4(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+        let segments = ip.segments();
+        let [.., a, b, c, d] = ip.octets();
+        match segments {
+            [0, 0, 0, 0, 0, 0xffff, _, _] | [0, 0, 0, 0, 0, 0, _, _] => {
+                Some(Ipv4Addr::new(a, b, c, d))
+            }
+            [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(Ipv4Addr::new(a, b, c, d)),
+            [0x2002, high, low, ..] => {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                Some(Ipv4Addr::new(a, b, c, d))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+        let first = ip.segments()[0];
+        ip.is_loopback()
+            || ip.is_unspecified()
+            || ip.is_multicast()
+            // fc00::/7 unique local and fe80::/10 link-local
+            || (first & 0xfe00) == 0xfc00
+            || (first & 0xffc0) == 0xfe80
+    }
+
+    /// Map the HTTP status code to the contract `Error`.
+    fn check_status(status_code: u16) -> Result<()> {
+        match status_code {
+            200..=299 => Ok(()),
+            400 | 422 => Err(Error::BadRequest),
+            401 | 403 => Err(Error::Unauthorized),
+            404 => Err(Error::NotFound),
+            429 => Err(Error::RateLimited),
+            408 | 504 | 524 => Err(Error::Timeout),
+            500..=599 => Err(Error::ServerFailure(status_code)),
+            _ => Err(Error::UnexpectedStatus(status_code)),
         }
     }
 
+    fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+        let mut buffer = [0u8; MAX_REQUEST_BODY];
+        let len =
+            serde_json_core::to_slice(value, &mut buffer).or(Err(Error::InvalidRequestBody))?;
+        Ok(buffer[..len].to_vec())
+    }
+
+    fn parse_json<T: for<'de> Deserialize<'de>>(response: &HttpResponse) -> Result<T> {
+        let (value, _) =
+            serde_json_core::from_slice(&response.body).or(Err(Error::InvalidResponseBody))?;
+        Ok(value)
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
+        use pink_extension::chain_extension::{mock, HttpRequest};
+
+        const BASE_URL: &str = "https://api.example.com/v1/";
+
+        fn response(status_code: u16, body: &[u8]) -> HttpResponse {
+            HttpResponse {
+                status_code,
+                reason_phrase: String::new(),
+                headers: Default::default(),
+                body: body.to_vec(),
+            }
+        }
+
+        fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+            request
+                .headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        }
+
+        fn contract(auth: Auth) -> {{ContractName}} {
+            {{ContractName}}::new(Config {
+                base_url: String::from(BASE_URL),
+                headers: alloc::vec![("User-Agent".into(), "phat-contract".into())],
+                auth,
+            })
+        }
+
+        /// Answer every request with `status_code` and an empty body.
+        fn mock_status(status_code: u16) {
+            mock::mock_http_request(move |_| response(status_code, b""));
+        }
+
         #[ink::test]
         fn get_ip_works() {
-            use pink_extension::chain_extension::{mock, HttpResponse};
-
             mock::mock_http_request(|request| {
                 if request.url == "https://ip.kvin.wang" {
                     HttpResponse::ok(b"1.1.1.1".to_vec())
                 } else {
-                HttpResponse::not_found()
+                    HttpResponse::not_found()
                 }
             });
 
-            let contract = {{contract_name}}::default();
+            let contract = {{ContractName}}::default();
             assert_eq!(contract.get_ip().1, b"1.1.1.1");
         }
+
+        #[ink::test]
+        fn get_todo_works() {
+            mock::mock_http_request(|request| {
+                assert_eq!(request.method, "GET");
+                assert_eq!(request.url, "https://api.example.com/v1/todos/1");
+                assert_eq!(header(&request, "Accept"), Some("application/json"));
+                assert_eq!(header(&request, "User-Agent"), Some("phat-contract"));
+                assert_eq!(header(&request, "Authorization"), Some("Bearer secret"));
+                assert_eq!(header(&request, "Content-Type"), None);
+                response(
+                    200,
+                    br#"{"userId":1,"id":1,"title":"delectus aut autem","completed":false}"#,
+                )
+            });
+
+            let todo = contract(Auth::Bearer("secret".into())).get_todo(1);
+            assert_eq!(
+                todo,
+                Ok(Todo {
+                    id: 1,
+                    title: String::from("delectus aut autem"),
+                    completed: false,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn create_todo_works() {
+            mock::mock_http_request(|request| {
+                assert_eq!(request.method, "POST");
+                assert_eq!(request.url, "https://api.example.com/v1/todos");
+                assert_eq!(header(&request, "Content-Type"), Some("application/json"));
+                assert_eq!(header(&request, "X-API-Key"), Some("secret"));
+                assert_eq!(
+                    request.body,
+                    br#"{"title":"write tests","completed":false}"#
+                );
+                response(
+                    201,
+                    br#"{"title":"write tests","completed":false,"id":201}"#,
+                )
+            });
+
+            let auth = Auth::ApiKey {
+                header: "X-API-Key".into(),
+                key: "secret".into(),
+            };
+            let todo = contract(auth).create_todo(String::from("write tests"));
+            assert_eq!(
+                todo,
+                Ok(Todo {
+                    id: 201,
+                    title: String::from("write tests"),
+                    completed: false,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn create_todo_rejects_oversized_body() {
+            mock::mock_http_request(|_| panic!("no request expected"));
+
+            let title = "a".repeat(MAX_REQUEST_BODY);
+            assert_eq!(
+                contract(Auth::None).create_todo(title),
+                Err(Error::InvalidRequestBody)
+            );
+        }
+
+        #[ink::test]
+        fn delete_todo_works() {
+            mock::mock_http_request(|request| {
+                assert_eq!(request.method, "DELETE");
+                assert_eq!(request.url, "https://api.example.com/v1/todos/1");
+                response(204, b"")
+            });
+
+            assert_eq!(contract(Auth::None).delete_todo(1), Ok(()));
+        }
+
+        #[ink::test]
+        fn maps_status_codes() {
+            for (status_code, error) in [
+                (400, Error::BadRequest),
+                (401, Error::Unauthorized),
+                (403, Error::Unauthorized),
+                (404, Error::NotFound),
+                (429, Error::RateLimited),
+                (500, Error::ServerFailure(500)),
+                (503, Error::ServerFailure(503)),
+                (302, Error::UnexpectedStatus(302)),
+            ] {
+                mock_status(status_code);
+                assert_eq!(contract(Auth::None).get_todo(1), Err(error));
+            }
+        }
+
+        #[ink::test]
+        fn reports_timeout() {
+            for status_code in [408, 504, 524] {
+                mock_status(status_code);
+                assert_eq!(contract(Auth::None).delete_todo(1), Err(Error::Timeout));
+            }
+        }
+
+        #[ink::test]
+        fn reports_malformed_body() {
+            mock::mock_http_request(|_| response(200, br#"{"id":1,"title":"#));
+
+            assert_eq!(
+                contract(Auth::None).get_todo(1),
+                Err(Error::InvalidResponseBody)
+            );
+        }
+
+        #[ink::test]
+        fn only_admin_sets_config() {
+            let mut contract = contract(Auth::None);
+            let accounts = ink::env::test::default_accounts::<PinkEnvironment>();
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            let config = Config {
+                base_url: String::from("https://attacker.example.com"),
+                headers: Vec::new(),
+                auth: Auth::None,
+            };
+            assert_eq!(contract.set_config(config), Err(Error::NoPermissions));
+            assert_eq!(contract.base_url(), BASE_URL);
+        }
+
+        fn proxy_contract() -> {{ContractName}} {
+            let mut contract = contract(Auth::None);
+            contract
+                .allow_proxy_origin(String::from("https://wttr.in"))
+                .unwrap();
+            contract
+        }
+
+        #[ink::test]
+        fn proxy_works() {
+            mock::mock_http_request(|request| {
+                assert_eq!(request.method, "GET");
+                assert_eq!(request.url, "https://wttr.in/berlin?ATm");
+                HttpResponse::ok(b"Berlin: +12C".to_vec())
+            });
+
+            let res = proxy_contract().proxy(String::from("https://wttr.in/berlin?ATm"));
+            assert_eq!(res, Ok(b"Berlin: +12C".to_vec()));
+        }
+
+        #[ink::test]
+        fn proxy_rejects_unsafe_urls() {
+            mock::mock_http_request(|_| panic!("no request expected"));
+
+            let contract = proxy_contract();
+            for (url, error) in [
+                ("http://wttr.in/berlin", ProxyError::NotHttps),
+                ("file:///etc/passwd", ProxyError::NotHttps),
+                ("https://example.com/", ProxyError::OriginNotAllowed),
+                ("https://wttr.in:8443/", ProxyError::OriginNotAllowed),
+                ("https://wttr.in.example.com/", ProxyError::OriginNotAllowed),
+                ("https://wttr.in@10.0.0.1/", ProxyError::InvalidUrl),
+                ("https://wttr.in%2f@10.0.0.1/", ProxyError::InvalidUrl),
+                ("https://0x7f.1/", ProxyError::InvalidUrl),
+                ("https://2130706433/", ProxyError::InvalidUrl),
+                ("wttr.in/berlin", ProxyError::InvalidUrl),
+                ("https://127.0.0.1/", ProxyError::PrivateAddress),
+                ("https://10.0.0.1/", ProxyError::PrivateAddress),
+                ("https://172.16.0.1/", ProxyError::PrivateAddress),
+                ("https://192.168.1.1/", ProxyError::PrivateAddress),
+                (
+                    "https://169.254.169.254/latest/meta-data",
+                    ProxyError::PrivateAddress,
+                ),
+                ("https://100.64.0.1/", ProxyError::PrivateAddress),
+                ("https://0.0.0.0/", ProxyError::PrivateAddress),
+                ("https://localhost/", ProxyError::PrivateAddress),
+                ("https://api.LOCALHOST./", ProxyError::PrivateAddress),
+                ("https://[::1]/", ProxyError::PrivateAddress),
+                ("https://[fd00::1]/", ProxyError::PrivateAddress),
+                ("https://[fe80::1]/", ProxyError::PrivateAddress),
+                ("https://[::ffff:10.0.0.1]/", ProxyError::PrivateAddress),
+                ("https://[::]/", ProxyError::PrivateAddress),
+                ("https://[::127.0.0.1]/", ProxyError::PrivateAddress),
+                ("https://[64:ff9b::a9fe:a9fe]/", ProxyError::PrivateAddress),
+                (
+                    "https://[64:ff9b::192.168.0.1]/",
+                    ProxyError::PrivateAddress,
+                ),
+                ("https://[2002:7f00:1::]/", ProxyError::PrivateAddress),
+                ("https://[2002:a00:1::1]/", ProxyError::PrivateAddress),
+            ] {
+                assert_eq!(contract.proxy(String::from(url)), Err(error), "{url}");
+            }
+        }
+
+        #[ink::test]
+        fn proxy_reports_status() {
+            mock_status(503);
+
+            let res = proxy_contract().proxy(String::from("https://wttr.in/berlin"));
+            assert_eq!(res, Err(ProxyError::Status(503)));
+        }
+
+        #[ink::test]
+        fn proxy_limits_response_size() {
+            mock::mock_http_request(|_| HttpResponse::ok(alloc::vec![b'x'; 1024]));
+
+            let mut contract = proxy_contract();
+            let url = String::from("https://wttr.in/berlin");
+            assert_eq!(contract.set_max_proxy_response_size(1024), Ok(()));
+            assert!(contract.proxy(url.clone()).is_ok());
+            assert_eq!(contract.set_max_proxy_response_size(1023), Ok(()));
+            assert_eq!(contract.proxy(url), Err(ProxyError::ResponseTooLarge));
+        }
+
+        #[ink::test]
+        fn manages_proxy_origins() {
+            let mut contract = proxy_contract();
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("HTTPS://Example.com:443/")),
+                Ok(())
+            );
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("http://example.com")),
+                Err(Error::InvalidOrigin(ProxyError::NotHttps))
+            );
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("https://192.168.1.1")),
+                Err(Error::InvalidOrigin(ProxyError::PrivateAddress))
+            );
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("https://[2002:a00:1::1]")),
+                Err(Error::InvalidOrigin(ProxyError::PrivateAddress))
+            );
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("https://[64:ff9b::808:808]")),
+                Ok(())
+            );
+            assert_eq!(
+                contract.proxy_origins(),
+                [
+                    "https://wttr.in",
+                    "https://example.com",
+                    "https://[64:ff9b::808:808]"
+                ]
+            );
+
+            assert_eq!(
+                contract.remove_proxy_origin(String::from("https://wttr.in/")),
+                Ok(())
+            );
+            assert_eq!(
+                contract.proxy_origins(),
+                ["https://example.com", "https://[64:ff9b::808:808]"]
+            );
+
+            let accounts = ink::env::test::default_accounts::<PinkEnvironment>();
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.allow_proxy_origin(String::from("https://attacker.example.com")),
+                Err(Error::NoPermissions)
+            );
+            assert_eq!(
+                contract.set_max_proxy_response_size(u32::MAX),
+                Err(Error::NoPermissions)
+            );
+        }
     }
 }
\ No newline at end of file
@@ -7,10 +7,14 @@ use pink_extension as pink;
 #[pink::contract(env=PinkEnvironment)]
 mod {{contract_name}} {
     use super::pink;
+    use pink::chain_extension::{HttpRequest, HttpResponse};
     use pink::{http_get, PinkEnvironment};
+    use ink::storage::Mapping;
+    use primitive_types::U256;
     use scale::{Decode, Encode};
     use serde::Deserialize;
     use alloc::string::String;
+    use alloc::vec::Vec;
     use alloc::format;
 
     // you have to use crates with `no_std` support in contract.
@@ -19,20 +23,60 @@ mod {{contract_name}} {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
+        NoPermissions,
         InvalidEthAddress,
         HttpRequestFailed,
         InvalidResponseBody,
+        /// No RPC endpoint is configured for the chain.
+        UnknownChain,
+        /// The backend rejected the request, e.g. because of an invalid API key.
+        BackendRejected(String),
     }
 
     /// Type alias for the contract's result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Chain id as defined by EIP-155, e.g. 1 for Ethereum mainnet.
+    pub type ChainId = u64;
+
+    /// Timeout of each batch of balance requests in `get_balances`.
+    const BATCH_TIMEOUT_MS: u64 = 10_000;
+
+    /// Most requests the Phala Worker accepts in one `batch_http_request`.
+    const MAX_BATCH_REQUESTS: usize = 5;
+
+    /// Where balances are queried from.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Backend {
+        /// The Etherscan API. It works without an API key, but is heavily rate limited then.
+        Etherscan { api_key: String },
+        /// Any Ethereum JSON-RPC endpoint, queried with `eth_getBalance`.
+        JsonRpc { url: String },
+    }
+
+    /// Prints the kind of backend only, as API keys are passed in the URLs of RPC providers too.
+    impl core::fmt::Debug for Backend {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Backend::Etherscan { .. } => f.write_str("Etherscan(***)"),
+                Backend::JsonRpc { .. } => f.write_str("JsonRpc(***)"),
+            }
+        }
+    }
+
     /// Defines the storage of your contract.
-    /// All the fields will be encrypted and stored on-chain.
-    /// In this stateless example, we just add a useless field for demo.
+    /// All the fields will be encrypted and stored on-chain,
+    /// so the API key of the backend stays private.
     #[ink(storage)]
     pub struct {{ContractName}} {
-        demo_field: bool,
+        admin: AccountId,
+        backend: Backend,
+        /// JSON-RPC endpoint of each chain queried by `get_balances`.
+        chains: Mapping<ChainId, String>,
     }
 
     #[derive(Deserialize, Encode, Clone, Debug, PartialEq)]
@@ -42,36 +86,228 @@ mod {{contract_name}} {
         result: &'a str,
     }
 
+    #[derive(Deserialize, Clone, Debug, PartialEq)]
+    pub struct JsonRpcResponse<'a> {
+        jsonrpc: &'a str,
+        id: u32,
+        #[serde(borrow, default)]
+        result: Option<&'a str>,
+        #[serde(borrow, default)]
+        error: Option<JsonRpcError<'a>>,
+    }
+
+    #[derive(Deserialize, Clone, Debug, PartialEq)]
+    pub struct JsonRpcError<'a> {
+        code: i32,
+        message: &'a str,
+    }
+
     impl {{ContractName}} {
         /// Constructor to initializes your contract
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self { demo_field: true }
+            Self {
+                admin: Self::env().caller(),
+                backend: Backend::Etherscan {
+                    api_key: String::new(),
+                },
+                chains: Mapping::default(),
+            }
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            if self.admin != self.env().caller() {
+                return Err(Error::NoPermissions);
+            }
+            Ok(())
+        }
+
+        /// Change the backend, e.g. to add an Etherscan API key. Only the admin can do this.
+        ///
+        /// Functions using `&mut self` are transactions and WILL change the contract state.
+        #[ink(message)]
+        pub fn set_backend(&mut self, backend: Backend) -> Result<()> {
+            self.ensure_admin()?;
+            self.backend = backend;
+            Ok(())
+        }
+
+        /// Set the JSON-RPC endpoint of a chain. Only the admin can do this.
+        #[ink(message)]
+        pub fn set_chain_rpc(&mut self, chain: ChainId, url: String) -> Result<()> {
+            self.ensure_admin()?;
+            self.chains.insert(chain, &url);
+            Ok(())
+        }
+
+        /// Remove the JSON-RPC endpoint of a chain. Only the admin can do this.
+        #[ink(message)]
+        pub fn remove_chain_rpc(&mut self, chain: ChainId) -> Result<()> {
+            self.ensure_admin()?;
+            self.chains.remove(chain);
+            Ok(())
+        }
+
+        /// The JSON-RPC endpoint configured for `chain`. Only the admin can see it, as RPC
+        /// URLs often carry an API key.
+        #[ink(message)]
+        pub fn chain_rpc(&self, chain: ChainId) -> Result<Option<String>> {
+            self.ensure_admin()?;
+            Ok(self.chains.get(chain))
         }
 
         /// A function to handle direct off-chain Query from users.
         /// Such functions use the immutable reference `&self`
         /// so WILL NOT change the contract state.
+        ///
+        /// Returns the balance of `account` in Wei.
         #[ink(message)]
-        pub fn get_eth_balance(&self, account: String) -> Result<String> {
-            if !account.starts_with("0x") && account.len() != 42 {
-                return Err(Error::InvalidEthAddress);
-            }
+        pub fn get_eth_balance(&self, account: String) -> Result<U256> {
+            validate_address(&account)?;
 
-            // get account ETH balance with HTTP requests to Etherscan
+            // get account ETH balance with HTTP requests to the backend
             // you can send any HTTP requests in Query handler
-            let resp = http_get!(format!(
-                "https://api.etherscan.io/api?module=account&action=balance&address={}",
-                account
-            ));
-            if resp.status_code != 200 {
-                return Err(Error::HttpRequestFailed);
+            match &self.backend {
+                Backend::Etherscan { api_key } => etherscan_balance(api_key, &account),
+                Backend::JsonRpc { url } => json_rpc_balance(url, &account),
             }
+        }
 
-            let result: EtherscanResponse = serde_json_core::from_slice(&resp.body)
-                .or(Err(Error::InvalidResponseBody))?
-                .0;
-            Ok(String::from(result.result))
+        /// Query the balance of `account` in Wei on each of `chains` at once.
+        ///
+        /// The requests are sent in batches of at most `MAX_BATCH_REQUESTS`, failures are
+        /// reported per chain, in the order of `chains`.
+        #[ink(message)]
+        pub fn get_balances(
+            &self,
+            account: String,
+            chains: Vec<ChainId>,
+        ) -> Result<Vec<(ChainId, Result<U256>)>> {
+            validate_address(&account)?;
+
+            let urls: Vec<_> = chains.iter().map(|chain| self.chains.get(chain)).collect();
+            let known: Vec<_> = urls.iter().flatten().collect();
+            let mut responses = Vec::with_capacity(known.len());
+            for batch in known.chunks(MAX_BATCH_REQUESTS) {
+                let requests = batch
+                    .iter()
+                    .map(|url| json_rpc_request(url, &account))
+                    .collect();
+                // A failed batch only fails its own chains, each request gets one response.
+                let batch_responses = pink::ext()
+                    .batch_http_request(requests, BATCH_TIMEOUT_MS)
+                    .unwrap_or_default();
+                responses.extend(
+                    batch_responses
+                        .into_iter()
+                        .map(|response| response.ok())
+                        .chain(core::iter::repeat_with(|| None))
+                        .take(batch.len()),
+                );
+            }
+            let mut responses = responses.into_iter();
+
+            let balances = chains
+                .into_iter()
+                .zip(urls)
+                .map(|(chain, url)| {
+                    let balance = match url {
+                        None => Err(Error::UnknownChain),
+                        Some(_) => match responses.next().flatten() {
+                            Some(resp) => parse_json_rpc_balance(resp),
+                            None => Err(Error::HttpRequestFailed),
+                        },
+                    };
+                    (chain, balance)
+                })
+                .collect();
+            Ok(balances)
+        }
+    }
+
+    /// Check that `account` is a `0x` prefixed hex address. Mixed case addresses have to carry
+    /// a valid EIP-55 checksum.
+    fn validate_address(account: &str) -> Result<()> {
+        let hex = account
+            .strip_prefix("0x")
+            .ok_or(Error::InvalidEthAddress)?;
+        if hex.len() != 40 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::InvalidEthAddress);
+        }
+        let has_lower = hex.bytes().any(|c| c.is_ascii_lowercase());
+        let has_upper = hex.bytes().any(|c| c.is_ascii_uppercase());
+        if has_lower && has_upper && !has_valid_checksum(hex) {
+            return Err(Error::InvalidEthAddress);
+        }
+        Ok(())
+    }
+
+    /// EIP-55: a letter is uppercase iff the matching nibble of the Keccak-256 hash of the
+    /// lowercase address is at least 8.
+    fn has_valid_checksum(hex: &str) -> bool {
+        let mut hash = [0u8; 32];
+        ink::env::hash_bytes::<ink::env::hash::Keccak256>(
+            hex.to_ascii_lowercase().as_bytes(),
+            &mut hash,
+        );
+        hex.bytes().enumerate().all(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            !c.is_ascii_alphabetic() || c.is_ascii_uppercase() == (nibble >= 8)
+        })
+    }
+
+    fn etherscan_balance(api_key: &str, account: &str) -> Result<U256> {
+        let resp = http_get!(format!(
+            "https://api.etherscan.io/api?module=account&action=balance&address={}&tag=latest&apikey={}",
+            account, api_key
+        ));
+        if resp.status_code != 200 {
+            return Err(Error::HttpRequestFailed);
+        }
+
+        let result: EtherscanResponse = serde_json_core::from_slice(&resp.body)
+            .or(Err(Error::InvalidResponseBody))?
+            .0;
+        if result.status != "1" {
+            return Err(Error::BackendRejected(String::from(result.result)));
+        }
+        U256::from_dec_str(result.result).or(Err(Error::InvalidResponseBody))
+    }
+
+    fn json_rpc_balance(url: &str, account: &str) -> Result<U256> {
+        parse_json_rpc_balance(pink::ext().http_request(json_rpc_request(url, account)))
+    }
+
+    fn json_rpc_request(url: &str, account: &str) -> HttpRequest {
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"eth_getBalance","params":["{}","latest"]}}"#,
+            account
+        );
+        let headers = alloc::vec![("Content-Type".into(), "application/json".into())];
+        HttpRequest::new(url, "POST", headers, body.into_bytes())
+    }
+
+    fn parse_json_rpc_balance(resp: HttpResponse) -> Result<U256> {
+        if resp.status_code != 200 {
+            return Err(Error::HttpRequestFailed);
+        }
+
+        let result: JsonRpcResponse = serde_json_core::from_slice(&resp.body)
+            .or(Err(Error::InvalidResponseBody))?
+            .0;
+        match (result.result, result.error) {
+            (Some(balance), None) => {
+                let hex = balance
+                    .strip_prefix("0x")
+                    .ok_or(Error::InvalidResponseBody)?;
+                U256::from_str_radix(hex, 16).or(Err(Error::InvalidResponseBody))
+            }
+            (_, Some(error)) => Err(Error::BackendRejected(String::from(error.message))),
+            (None, None) => Err(Error::InvalidResponseBody),
         }
     }
 
@@ -82,21 +318,361 @@ mod {{contract_name}} {
     mod tests {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use pink::chain_extension::{
+            func_ids, mock, BatchHttpResult, EncodeOutput, HttpRequestError,
+        };
+        use std::{cell::RefCell, rc::Rc};
+
+        const ACCOUNT: &str = "0xD0fE316B9f01A3b5fd6790F88C2D53739F80B464";
+        const RPC_URL: &str = "https://rpc.example.com/";
+
+        fn response(status_code: u16, body: &[u8]) -> HttpResponse {
+            HttpResponse {
+                status_code,
+                reason_phrase: String::new(),
+                headers: Default::default(),
+                body: body.to_vec(),
+            }
+        }
+
+        /// Answer the Etherscan balance request of `ACCOUNT` with a recorded response,
+        /// so the tests run without network access.
+        fn mock_etherscan(status_code: u16, body: &'static [u8]) {
+            mock::mock_http_request(move |request| {
+                assert_eq!(request.method, "GET");
+                assert!(request.url.starts_with("https://api.etherscan.io/api?"));
+                assert!(request.url.contains(ACCOUNT));
+                response(status_code, body)
+            });
+        }
+
+        /// Answer the `eth_getBalance` request of `ACCOUNT` with a recorded response.
+        fn mock_json_rpc(status_code: u16, body: &'static [u8]) {
+            mock::mock_http_request(move |request| {
+                assert_eq!(request.method, "POST");
+                assert_eq!(request.url, RPC_URL);
+                let payload = String::from_utf8(request.body).unwrap();
+                assert!(payload.contains(r#""method":"eth_getBalance""#));
+                assert!(payload.contains(ACCOUNT));
+                response(status_code, body)
+            });
+        }
+
+        fn json_rpc_contract() -> {{ContractName}} {
+            let mut {{contract_name}} = {{ContractName}}::new();
+            let backend = Backend::JsonRpc {
+                url: String::from(RPC_URL),
+            };
+            {{contract_name}}.set_backend(backend).unwrap();
+            {{contract_name}}
+        }
+
+        /// Answer `batch_http_request` the way the Phala Worker does.
+        ///
+        /// `mock::mock_batch_http_request` returns the whole encoded `Result` with a success
+        /// status, which the contract can't decode, so the batch error is reported through the
+        /// status code here.
+        struct MockBatchHttp<F>(F);
+
+        impl<F> ink::env::test::ChainExtension for MockBatchHttp<F>
+        where
+            F: FnMut(Vec<HttpRequest>) -> BatchHttpResult,
+        {
+            fn func_id(&self) -> u32 {
+                func_ids::BATCH_HTTP_REQUEST
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                let input: Vec<u8> = Decode::decode(&mut &input[..]).unwrap();
+                let (requests, _timeout_ms): (Vec<HttpRequest>, u64) =
+                    Decode::decode(&mut &input[..]).unwrap();
+                let (status, out) = EncodeOutput((self.0)(requests)).encode();
+                output.extend(out);
+                status
+            }
+        }
+
+        fn mock_batch_http_request(
+            call: impl FnMut(Vec<HttpRequest>) -> BatchHttpResult + 'static,
+        ) {
+            ink::env::test::register_chain_extension(MockBatchHttp(call));
+        }
+
+        fn multi_chain_contract() -> {{ContractName}} {
+            let mut {{contract_name}} = {{ContractName}}::new();
+            for (chain, url) in [
+                (1, "https://eth.example.com/"),
+                (10, "https://optimism.example.com/"),
+                (137, "https://polygon.example.com/"),
+            ] {
+                {{contract_name}}.set_chain_rpc(chain, String::from(url)).unwrap();
+            }
+            {{contract_name}}
+        }
+
+        fn contract_with_chains(chains: &[ChainId]) -> {{ContractName}} {
+            let mut {{contract_name}} = {{ContractName}}::new();
+            for chain in chains {
+                {{contract_name}}
+                    .set_chain_rpc(*chain, format!("https://{chain}.example.com/"))
+                    .unwrap();
+            }
+            {{contract_name}}
+        }
 
         /// We test a simple use case of our contract.
         #[ink::test]
         fn it_works() {
             // when your contract is really deployed, the Phala Worker will do the HTTP requests
             // mock is needed for local test
-            pink_extension_runtime::mock_ext::mock_all_ext();
+            mock_etherscan(200, include_bytes!("fixtures/balance_ok.json"));
 
             let {{contract_name}} = {{ContractName}}::new();
-            let account = String::from("0xD0fE316B9f01A3b5fd6790F88C2D53739F80B464");
-            let res = {{contract_name}}.get_eth_balance(account.clone());
-            assert!(res.is_ok());
+            let res = {{contract_name}}.get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Ok(U256::from(40_891_626_854_930_000_000_000u128)));
 
             // run with `cargo +nightly test -- --nocapture` to see the following output
-            println!("Account {} gets {} Wei", account, res.unwrap());
+            println!("Account {} gets {} Wei", ACCOUNT, res.unwrap());
+        }
+
+        #[ink::test]
+        fn validates_addresses() {
+            assert_eq!(validate_address(ACCOUNT), Ok(()));
+            assert_eq!(validate_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"), Ok(()));
+            assert_eq!(validate_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"), Ok(()));
+            assert_eq!(validate_address("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"), Ok(()));
+            for invalid in [
+                "D0fE316B9f01A3b5fd6790F88C2D53739F80B464",
+                "0xD0fE316B9f01",
+                "0xD0fE316B9f01A3b5fd6790F88C2D53739F80B46Z",
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            ] {
+                assert_eq!(validate_address(invalid), Err(Error::InvalidEthAddress));
+            }
+        }
+
+        #[ink::test]
+        fn rejects_invalid_address() {
+            mock::mock_http_request(|_| panic!("no request expected"));
+
+            let {{contract_name}} = {{ContractName}}::new();
+            let res = {{contract_name}}.get_eth_balance(String::from("D0fE316B9f01"));
+            assert_eq!(res, Err(Error::InvalidEthAddress));
+        }
+
+        #[ink::test]
+        fn reports_http_error() {
+            mock_etherscan(503, include_bytes!("fixtures/service_unavailable.html"));
+
+            let {{contract_name}} = {{ContractName}}::new();
+            let res = {{contract_name}}.get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Err(Error::HttpRequestFailed));
+        }
+
+        #[ink::test]
+        fn reports_malformed_body() {
+            mock_etherscan(200, include_bytes!("fixtures/balance_truncated.json"));
+
+            let {{contract_name}} = {{ContractName}}::new();
+            let res = {{contract_name}}.get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Err(Error::InvalidResponseBody));
+        }
+
+        #[ink::test]
+        fn reports_etherscan_error() {
+            mock_etherscan(200, include_bytes!("fixtures/balance_invalid_key.json"));
+
+            let {{contract_name}} = {{ContractName}}::new();
+            let res = {{contract_name}}.get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Err(Error::BackendRejected(String::from("Invalid API Key"))));
+        }
+
+        #[ink::test]
+        fn sends_api_key() {
+            mock::mock_http_request(|request| {
+                assert!(request.url.ends_with("&apikey=MY_KEY"));
+                response(200, include_bytes!("fixtures/balance_ok.json"))
+            });
+
+            let mut {{contract_name}} = {{ContractName}}::new();
+            let backend = Backend::Etherscan {
+                api_key: String::from("MY_KEY"),
+            };
+            assert_eq!({{contract_name}}.set_backend(backend), Ok(()));
+            assert!({{contract_name}}.get_eth_balance(String::from(ACCOUNT)).is_ok());
+        }
+
+        #[test]
+        fn backend_debug_hides_credentials() {
+            let etherscan = Backend::Etherscan {
+                api_key: String::from("MY_KEY"),
+            };
+            assert_eq!(format!("{etherscan:?}"), "Etherscan(***)");
+            let json_rpc = Backend::JsonRpc {
+                url: String::from("https://eth.example.com/v3/MY_KEY"),
+            };
+            assert_eq!(format!("{json_rpc:?}"), "JsonRpc(***)");
+        }
+
+        #[ink::test]
+        fn only_admin_sets_backend() {
+            let mut {{contract_name}} = {{ContractName}}::new();
+            let accounts = ink::env::test::default_accounts::<PinkEnvironment>();
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            let backend = Backend::JsonRpc {
+                url: String::from(RPC_URL),
+            };
+            assert_eq!({{contract_name}}.set_backend(backend), Err(Error::NoPermissions));
+        }
+
+        #[ink::test]
+        fn json_rpc_works() {
+            mock_json_rpc(200, include_bytes!("fixtures/eth_get_balance_ok.json"));
+
+            let res = json_rpc_contract().get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Ok(U256::from(2_000_000_000_000_000_000u128)));
+        }
+
+        #[ink::test]
+        fn json_rpc_reports_error() {
+            mock_json_rpc(200, include_bytes!("fixtures/eth_get_balance_error.json"));
+
+            let res = json_rpc_contract().get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Err(Error::BackendRejected(String::from("header not found"))));
+        }
+
+        #[ink::test]
+        fn json_rpc_reports_http_error() {
+            mock_json_rpc(503, include_bytes!("fixtures/service_unavailable.html"));
+
+            let res = json_rpc_contract().get_eth_balance(String::from(ACCOUNT));
+            assert_eq!(res, Err(Error::HttpRequestFailed));
+        }
+
+        #[ink::test]
+        fn get_balances_reports_each_chain() {
+            mock_batch_http_request(|requests| {
+                let responses = requests
+                    .into_iter()
+                    .map(|request| {
+                        assert_eq!(request.method, "POST");
+                        match request.url.as_str() {
+                            "https://eth.example.com/" => Ok(response(
+                                200,
+                                include_bytes!("fixtures/eth_get_balance_ok.json"),
+                            )),
+                            "https://optimism.example.com/" => Err(HttpRequestError::Timeout),
+                            "https://polygon.example.com/" => Ok(response(
+                                200,
+                                include_bytes!("fixtures/eth_get_balance_error.json"),
+                            )),
+                            url => panic!("unexpected request to {url}"),
+                        }
+                    })
+                    .collect();
+                Ok(responses)
+            });
+
+            let res = multi_chain_contract().get_balances(String::from(ACCOUNT), vec![1, 10, 56, 137]);
+            assert_eq!(
+                res,
+                Ok(vec![
+                    (1, Ok(U256::from(2_000_000_000_000_000_000u128))),
+                    (10, Err(Error::HttpRequestFailed)),
+                    (56, Err(Error::UnknownChain)),
+                    (137, Err(Error::BackendRejected(String::from("header not found")))),
+                ])
+            );
+        }
+
+        #[ink::test]
+        fn get_balances_splits_batches() {
+            let batches = Rc::new(RefCell::new(Vec::new()));
+            {
+                let batches = batches.clone();
+                mock_batch_http_request(move |requests| {
+                    assert!(requests.len() <= MAX_BATCH_REQUESTS);
+                    batches.borrow_mut().push(requests.len());
+                    Ok(requests
+                        .iter()
+                        .map(|_| {
+                            Ok(response(
+                                200,
+                                include_bytes!("fixtures/eth_get_balance_ok.json"),
+                            ))
+                        })
+                        .collect())
+                });
+            }
+            let chains: Vec<ChainId> = (1..=12).collect();
+            let res = contract_with_chains(&chains)
+                .get_balances(String::from(ACCOUNT), chains)
+                .unwrap();
+            assert_eq!(*batches.borrow(), [5, 5, 2]);
+            assert_eq!(res.len(), 12);
+            assert!(res.iter().all(|(_, balance)| balance.is_ok()));
+        }
+
+        #[ink::test]
+        fn get_balances_reports_batch_error() {
+            let mut batch = 0;
+            mock_batch_http_request(move |requests| {
+                batch += 1;
+                if batch == 2 {
+                    return Err(HttpRequestError::TooManyRequests);
+                }
+                Ok(requests
+                    .iter()
+                    .map(|_| {
+                        Ok(response(
+                            200,
+                            include_bytes!("fixtures/eth_get_balance_ok.json"),
+                        ))
+                    })
+                    .collect())
+            });
+            let chains: Vec<ChainId> = (1..=12).collect();
+            let res = contract_with_chains(&chains)
+                .get_balances(String::from(ACCOUNT), chains)
+                .unwrap();
+            assert_eq!(res.len(), 12);
+            let balance = U256::from(2_000_000_000_000_000_000u128);
+            for (chain, result) in res {
+                if (6..=10).contains(&chain) {
+                    assert_eq!(result, Err(Error::HttpRequestFailed), "chain {chain}");
+                } else {
+                    assert_eq!(result, Ok(balance), "chain {chain}");
+                }
+            }
+        }
+
+        #[ink::test]
+        fn get_balances_rejects_invalid_address() {
+            mock_batch_http_request(|_| panic!("no request expected"));
+
+            let res = multi_chain_contract().get_balances(String::from("D0fE316B9f01"), vec![1]);
+            assert_eq!(res, Err(Error::InvalidEthAddress));
+        }
+
+        #[ink::test]
+        fn only_admin_sets_chains() {
+            let mut {{contract_name}} = multi_chain_contract();
+            let accounts = ink::env::test::default_accounts::<PinkEnvironment>();
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            assert_eq!(
+                {{contract_name}}.set_chain_rpc(56, String::from("https://bsc.example.com/")),
+                Err(Error::NoPermissions)
+            );
+            assert_eq!({{contract_name}}.remove_chain_rpc(1), Err(Error::NoPermissions));
+            assert_eq!({{contract_name}}.chain_rpc(10), Err(Error::NoPermissions));
+
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.alice);
+            assert_eq!({{contract_name}}.remove_chain_rpc(1), Ok(()));
+            assert_eq!({{contract_name}}.chain_rpc(1), Ok(None));
+            assert_eq!(
+                {{contract_name}}.chain_rpc(10),
+                Ok(Some(String::from("https://optimism.example.com/")))
+            );
         }
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-import { ContractType } from '@devphase/service';
+import { ContractType, TxHandler, waitFor } from '@devphase/service';
 import * as PhalaSdk from '@phala/sdk';
 import type { KeyringPair } from '@polkadot/keyring/types';
 import { stringToHex } from '@polkadot/util';
@@ -26,12 +26,38 @@ describe('{{ContractName}}', () => {
 
     describe('default constructor', () => {
         before(async function() {
-            contract = await factory.instantiate('default', []);
+            // the instantiating account is the admin of the contract
+            contract = await factory.instantiate('default', [], { asAccount: signer });
+
+            // only allowed origins can be proxied
+            await TxHandler.handle(
+                contract.tx.allowProxyOrigin({ gasLimit: 10e12 }, 'https://wttr.in'),
+                signer,
+                true
+            );
+
+            await waitFor(async() => {
+                const response = await contract.query.proxyOrigins(signer.address, { cert });
+                const output : any = response.output.toJSON();
+                return output.ok?.includes('https://wttr.in');
+            }, this.devPhase.waitTime);
         });
 
-        it('Should be able...', async function() {
+        it('Should be able to proxy a request to an allowed origin', async function() {
             const response = await contract.query.proxy(signer.address, { cert }, 'https://wttr.in/berlin?ATm');
-            console.log(response.output.toJSON());
+            const output : any = response.output.toJSON();
+            expect(output.ok).to.have.property('ok');
+        });
+
+        it('Should reject origins which are not allowed', async function() {
+            const response = await contract.query.proxy(signer.address, { cert }, 'https://example.com/');
+            expect(response.output.toJSON()).to.be.eql({ ok: { err: 'OriginNotAllowed' } });
+        });
+
+        it('Should be able to get a todo from the REST API', async function() {
+            const response = await contract.query.getTodo(signer.address, { cert }, 1);
+            const output : any = response.output.toJSON();
+            expect(output.ok.ok.id).to.be.equal(1);
         });
     });
 });
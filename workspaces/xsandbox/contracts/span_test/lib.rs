@@ -0,0 +1,74 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+extern crate alloc;
+
+/// Contract entering `logging` spans, to check that its records are tagged through the
+/// registered `TagStack` driver.
+#[pink::contract(env = PinkEnvironment)]
+mod span_test {
+    use alloc::{rc::Rc, string::String, vec::Vec};
+    use core::cell::RefCell;
+    use pink::PinkEnvironment;
+
+    /// Tags and message of a logged record.
+    pub type TaggedRecord = (Vec<String>, String);
+
+    /// Keeps the records logged in the current call.
+    struct Collector(Rc<RefCell<Vec<TaggedRecord>>>);
+
+    impl logging::Sink for Collector {
+        fn log(&self, record: &logging::Record) {
+            self.0
+                .borrow_mut()
+                .push((record.tags.to_vec(), record.message.into()));
+        }
+    }
+
+    #[ink(storage)]
+    pub struct SpanTest {}
+
+    impl SpanTest {
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self {}
+        }
+
+        /// Log in nested spans and return the records with their tags.
+        #[ink(message)]
+        pub fn tagged_records(&self) -> Vec<TaggedRecord> {
+            let records = Rc::new(RefCell::new(Vec::new()));
+            logging::add_sink(Collector(records.clone()));
+            {
+                let _outer = logging::enter_span("outer");
+                logging::info!("first");
+                let _inner = logging::enter_span("inner");
+                logging::info!("second");
+            }
+            logging::info!("untagged");
+            logging::reset_sinks();
+            let records = records.borrow().clone();
+            records
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn tags_records_of_spans() {
+            logging::testing::install();
+            let records = SpanTest::default().tagged_records();
+            assert_eq!(
+                records,
+                [
+                    (vec![String::from("outer")], String::from("first")),
+                    (
+                        vec![String::from("outer"), String::from("inner")],
+                        String::from("second")
+                    ),
+                    (vec![], String::from("untagged")),
+                ]
+            );
+        }
+    }
+}
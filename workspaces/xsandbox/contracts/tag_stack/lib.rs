@@ -0,0 +1,83 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+extern crate alloc;
+
+/// Reference implementation of the `TagStack` driver used by `logging::enter_span`.
+///
+/// Each calling contract gets its own stack of tags, so spans of different contracts
+/// never interleave.
+#[pink::contract(env = PinkEnvironment)]
+mod tag_stack {
+    use alloc::{string::String, vec::Vec};
+    use ink::storage::Mapping;
+    use pink::PinkEnvironment;
+
+    #[ink(storage)]
+    pub struct TagStack {
+        stacks: Mapping<AccountId, Vec<String>>,
+    }
+
+    impl TagStack {
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self {
+                stacks: Mapping::default(),
+            }
+        }
+    }
+
+    impl logging::TagStack for TagStack {
+        #[ink(message)]
+        fn push_tag(&mut self, tag: String) {
+            let caller = self.env().caller();
+            let mut tags = self.stacks.get(caller).unwrap_or_default();
+            tags.push(tag);
+            self.stacks.insert(caller, &tags);
+        }
+
+        #[ink(message)]
+        fn pop_tag(&mut self) {
+            let caller = self.env().caller();
+            let mut tags = self.stacks.get(caller).unwrap_or_default();
+            tags.pop();
+            if tags.is_empty() {
+                self.stacks.remove(caller);
+            } else {
+                self.stacks.insert(caller, &tags);
+            }
+        }
+
+        #[ink(message)]
+        fn tags(&self) -> Vec<String> {
+            self.stacks.get(self.env().caller()).unwrap_or_default()
+        }
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use logging::TagStack as _;
+
+        #[ink::test]
+        fn keeps_a_stack_per_caller() {
+            let accounts = ink::env::test::default_accounts::<PinkEnvironment>();
+            let mut stack = TagStack::default();
+
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.alice);
+            stack.push_tag("alice-outer".into());
+            stack.push_tag("alice-inner".into());
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            stack.push_tag("bob".into());
+            assert_eq!(stack.tags(), ["bob"]);
+
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.alice);
+            assert_eq!(stack.tags(), ["alice-outer", "alice-inner"]);
+            stack.pop_tag();
+            assert_eq!(stack.tags(), ["alice-outer"]);
+            stack.pop_tag();
+            stack.pop_tag();
+            assert!(stack.tags().is_empty());
+
+            ink::env::test::set_caller::<PinkEnvironment>(accounts.bob);
+            assert_eq!(stack.tags(), ["bob"]);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Bridge routing records of the `log` facade through [`crate::log_target`].
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// A `log::Log` implementation forwarding records to [`crate::log_target`], so they get the
+/// current `TagStack` prefix like records of the crate's own macros, and keep their target for
+/// the runtime [`crate::filter`].
+pub struct PinkLog;
+
+impl Log for PinkLog {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= max_level()
+            && crate::filter::enabled(level_of(metadata.level()).into(), metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            crate::log_target(
+                level_of(record.level()),
+                record.target(),
+                &[],
+                *record.args(),
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn level_of(level: Level) -> crate::Level {
+    match level {
+        Level::Error => crate::Level::Error,
+        Level::Warn => crate::Level::Warn,
+        Level::Info => crate::Level::Info,
+        Level::Debug => crate::Level::Debug,
+        Level::Trace => crate::Level::Trace,
+    }
+}
+
+/// The `log` counterpart of [`crate::STATIC_MAX_LEVEL`], so crates using the facade skip
+/// formatting records which would be discarded anyway.
+fn max_level() -> LevelFilter {
+    match crate::STATIC_MAX_LEVEL {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+static LOGGER: PinkLog = PinkLog;
+
+/// Install [`PinkLog`] as the global logger of the `log` facade.
+///
+/// Every contract call runs in a fresh instance, so this has to be called at the beginning
+/// of each message relying on it. Calling it more than once is harmless.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(max_level());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        filter::{set_filter, Filter},
+        testing::{captured_logs, install},
+    };
+
+    fn log(level: Level, target: &str, message: &str) {
+        Log::log(
+            &PinkLog,
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .args(format_args!("{message}"))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn forwards_records_with_level_and_target() {
+        install();
+        let _span = crate::enter_span("bridge");
+        log(Level::Warn, "dep::http", "slow");
+        log(Level::Trace, "dep", "details");
+
+        let logs = captured_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].level, crate::Level::Warn);
+        assert_eq!(logs[0].target, "dep::http");
+        assert_eq!(logs[0].tags, ["bridge"]);
+        assert_eq!(logs[0].message, "slow");
+        assert_eq!(logs[1].level, crate::Level::Trace);
+    }
+
+    #[test]
+    fn applies_the_runtime_filter() {
+        install();
+        set_filter(&Filter::parse("trace,dep=warn").unwrap()).unwrap();
+        let metadata = |level| Metadata::builder().level(level).target("dep::http").build();
+        assert!(PinkLog.enabled(&metadata(Level::Warn)));
+        assert!(!PinkLog.enabled(&metadata(Level::Info)));
+
+        log(Level::Info, "dep::http", "dropped");
+        log(Level::Error, "dep::http", "kept");
+        log(Level::Debug, "other", "kept too");
+        let messages: Vec<_> = captured_logs().into_iter().map(|log| log.message).collect();
+        assert_eq!(messages, ["kept", "kept too"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Optional buffering of records, flushed as few combined records.
+//!
+//! Each record is otherwise a separate `pink::ext().log` chain-extension call. With buffering
+//! enabled, records are accumulated in memory and emitted when the outermost [`crate::Span`]
+//! is exited, when [`crate::flush`] is called, or once the buffer grows over its size limit.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::local::call_local;
+
+/// Buffered records of the current call.
+struct Buffer {
+    records: Vec<(u8, String)>,
+    size: usize,
+    limit: usize,
+}
+
+call_local! {
+    static BUFFER: Option<Buffer> = None;
+    static DEPTH: usize = 0;
+}
+
+/// Start buffering records of the current call until at least `limit` bytes are pending.
+pub fn enable_buffering(limit: usize) {
+    BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        match buffer.as_mut() {
+            Some(buffer) => buffer.limit = limit,
+            None => {
+                *buffer = Some(Buffer {
+                    records: Vec::new(),
+                    size: 0,
+                    limit,
+                })
+            }
+        }
+    });
+}
+
+/// Flush the pending records and log each following record directly again.
+pub fn disable_buffering() {
+    flush();
+    BUFFER.with(|buffer| *buffer.borrow_mut() = None);
+}
+
+/// Emit the pending records, combining each run of consecutive records at the same level into
+/// one record at that level, so the worker's level filter still applies to every line.
+pub fn flush() {
+    let records = BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        let buffer = buffer.as_mut()?;
+        buffer.size = 0;
+        Some(core::mem::take(&mut buffer.records))
+    });
+    let Some(records) = records else {
+        return;
+    };
+    let mut records = records.into_iter().peekable();
+    while let Some((level, mut combined)) = records.next() {
+        while let Some((_, line)) = records.next_if(|(next, _)| *next == level) {
+            combined.push('\n');
+            combined.push_str(&line);
+        }
+        pink::ext().log(level, &combined);
+    }
+}
+
+/// Log a formatted record, or keep it for later when buffering.
+pub(crate) fn emit(level: u8, line: String) {
+    let over_limit = BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        let Some(buffer) = buffer.as_mut() else {
+            pink::ext().log(level, &line);
+            return false;
+        };
+        buffer.size += line.len();
+        buffer.records.push((level, line));
+        buffer.size >= buffer.limit
+    });
+    if over_limit {
+        flush();
+    }
+}
+
+/// Whether a span is entered, so the outermost one is going to flush when exited.
+pub(crate) fn in_span() -> bool {
+    DEPTH.with(|depth| *depth.borrow() > 0)
+}
+
+/// Record that a span was entered.
+pub(crate) fn enter() {
+    DEPTH.with(|depth| *depth.borrow_mut() += 1);
+}
+
+/// Record that a span was exited, reporting the suppressed records and flushing the buffer when
+/// it was the outermost one.
+pub(crate) fn exit() {
+    let outermost = DEPTH.with(|depth| {
+        let mut depth = depth.borrow_mut();
+        *depth = depth.saturating_sub(1);
+        *depth == 0
+    });
+    if outermost {
+        crate::rate::report();
+        crate::flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pink::chain_extension::mock;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Capture the `pink::ext().log` calls.
+    fn mock_log() -> Rc<RefCell<Vec<(u8, String)>>> {
+        crate::testing::install();
+        let logged: Rc<RefCell<Vec<(u8, String)>>> = Default::default();
+        let sink = logged.clone();
+        mock::mock_log(move |level, message| sink.borrow_mut().push((level, message.into())));
+        logged
+    }
+
+    #[test]
+    fn combines_runs_of_the_same_level() {
+        let logged = mock_log();
+        enable_buffering(usize::MAX);
+        for (level, line) in [(4, "a"), (4, "b"), (1, "c"), (4, "d"), (5, "e"), (5, "f")] {
+            emit(level, line.into());
+        }
+        assert!(logged.borrow().is_empty());
+
+        flush();
+        assert_eq!(
+            *logged.borrow(),
+            [
+                (4, "a\nb".into()),
+                (1, "c".into()),
+                (4, "d".into()),
+                (5, "e\nf".into()),
+            ]
+        );
+        flush();
+        assert_eq!(logged.borrow().len(), 4);
+    }
+
+    #[test]
+    fn flushes_over_limit_and_at_outermost_span() {
+        let logged = mock_log();
+        enable_buffering(4);
+        emit(3, "abc".into());
+        emit(3, "de".into());
+        assert_eq!(*logged.borrow(), [(3, "abc\nde".into())]);
+
+        enter();
+        enter();
+        emit(3, "f".into());
+        exit();
+        assert_eq!(logged.borrow().len(), 1);
+        exit();
+        assert_eq!(logged.borrow()[1], (3, "f".into()));
+    }
+
+    #[test]
+    fn placeholder_spans_do_not_flush() {
+        let logged = mock_log();
+        enable_buffering(usize::MAX);
+        {
+            let _span = crate::enter_span("outer");
+            crate::info!("one");
+            drop(crate::Span::default());
+            assert!(logged.borrow().is_empty());
+        }
+        assert_eq!(logged.borrow().len(), 1);
+    }
+
+    #[test]
+    fn logs_directly_when_disabled() {
+        let logged = mock_log();
+        emit(2, "direct".into());
+        enable_buffering(usize::MAX);
+        emit(2, "kept".into());
+        disable_buffering();
+        emit(2, "direct again".into());
+        assert_eq!(
+            *logged.borrow(),
+            [
+                (2, "direct".into()),
+                (2, "kept".into()),
+                (2, "direct again".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Propagation of span context across contract calls.
+//!
+//! A callee starts with its own, unrelated tag stack. To keep the call path in its records, the
+//! caller appends the current [`SpanContext`] to the call arguments with [`with_span_context`]
+//! and the callee takes it as its last message argument and passes it to
+//! [`import_span_context`]:
+//!
+//! ```ignore
+//! // Caller
+//! let _span = logging::enter_span("fetch");
+//! build_call::<PinkEnvironment>()
+//!     .call(callee)
+//!     .exec_input(logging::with_span_context(
+//!         ExecutionInput::new(Selector::new(ink::selector_bytes!("get_price"))).push_arg(token),
+//!     ))
+//!     .returns::<u128>()
+//!     .invoke();
+//!
+//! // Callee
+//! #[ink(message)]
+//! pub fn get_price(&self, token: String, context: logging::SpanContext) -> u128 {
+//!     let _span = logging::import_span_context(context);
+//!     let _inner = logging::enter_span("get_price");
+//!     // records are tagged `[fetch,get_price]` with the caller's correlation ID
+//! }
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use ink::env::call::{
+    utils::{ArgsList, EmptyArgumentList},
+    ExecutionInput,
+};
+use scale::{Decode, Encode};
+
+use crate::{buffer, correlation, current_tags, enter_span, Span};
+
+/// The span tags and correlation ID of a call, passed on to the contracts it calls.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct SpanContext {
+    pub tags: Vec<String>,
+    pub correlation_id: Option<String>,
+}
+
+impl SpanContext {
+    /// Export the context of the current call.
+    pub fn current() -> Self {
+        Self {
+            tags: current_tags().unwrap_or_default(),
+            correlation_id: correlation::correlation_id(),
+        }
+    }
+}
+
+/// Continue the span context of the caller in the current call.
+///
+/// The correlation ID is adopted and the caller's tags prefix those of the following spans
+/// until the returned span is exited.
+pub fn import_span_context(context: SpanContext) -> Span {
+    if let Some(id) = context.correlation_id {
+        correlation::set_correlation_id(id);
+    }
+    if context.tags.is_empty() {
+        buffer::enter();
+        return Span {
+            name: String::new(),
+            bag: None,
+            timer: None,
+            entered: true,
+        };
+    }
+    enter_span(&context.tags.join(","))
+}
+
+/// Arguments of a call which the [`SpanContext`] can be appended to.
+pub trait PushSpanContext {
+    type Output;
+
+    fn push_span_context(self, context: SpanContext) -> Self::Output;
+}
+
+impl PushSpanContext for ExecutionInput<EmptyArgumentList> {
+    type Output = ExecutionInput<ArgsList<SpanContext, EmptyArgumentList>>;
+
+    fn push_span_context(self, context: SpanContext) -> Self::Output {
+        self.push_arg(context)
+    }
+}
+
+impl<Head, Rest> PushSpanContext for ExecutionInput<ArgsList<Head, Rest>> {
+    type Output = ExecutionInput<ArgsList<SpanContext, ArgsList<Head, Rest>>>;
+
+    fn push_span_context(self, context: SpanContext) -> Self::Output {
+        self.push_arg(context)
+    }
+}
+
+/// Append the current [`SpanContext`] as the last argument of a cross-contract call.
+pub fn with_span_context<Input: PushSpanContext>(input: Input) -> Input::Output {
+    input.push_span_context(SpanContext::current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{captured_logs, install};
+    use ink::env::call::Selector;
+
+    #[test]
+    fn exports_the_current_context() {
+        install();
+        assert_eq!(SpanContext::current(), SpanContext::default());
+        let _outer = enter_span("outer");
+        let _inner = enter_span("inner");
+        assert_eq!(
+            SpanContext::current(),
+            SpanContext {
+                tags: vec!["outer".into(), "inner".into()],
+                correlation_id: Some("0000000000000001".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn imports_the_caller_context() {
+        install();
+        {
+            let _span = import_span_context(SpanContext {
+                tags: vec!["fetch".into(), "retry".into()],
+                correlation_id: Some("c1".into()),
+            });
+            let _inner = enter_span("get_price");
+            crate::info!("done");
+        }
+        let log = &captured_logs()[0];
+        assert_eq!(log.tags, ["fetch,retry", "get_price"]);
+        assert_eq!(log.correlation_id.as_deref(), Some("c1"));
+        assert_eq!(current_tags(), None);
+    }
+
+    #[test]
+    fn imports_an_empty_context_balanced() {
+        install();
+        crate::enable_buffering(usize::MAX);
+        let outer = enter_span("outer");
+        {
+            let _span = import_span_context(SpanContext {
+                tags: Vec::new(),
+                correlation_id: Some("c1".into()),
+            });
+            assert_eq!(current_tags(), Some(vec!["outer".into()]));
+            crate::info!("kept");
+        }
+        assert_eq!(current_tags(), Some(vec!["outer".into()]));
+        assert_eq!(crate::correlation_id().as_deref(), Some("c1"));
+        // still buffered, only leaving the outer span flushes
+        let logged = std::rc::Rc::new(core::cell::Cell::new(false));
+        let flag = logged.clone();
+        pink::chain_extension::mock::mock_log(move |_, _| flag.set(true));
+        drop(outer);
+        assert!(logged.get());
+    }
+
+    #[test]
+    fn appends_the_context_as_last_argument() {
+        install();
+        let _span = enter_span("fetch");
+        let selector = [1, 2, 3, 4];
+        let input = with_span_context(
+            ExecutionInput::new(Selector::new(selector))
+                .push_arg(7u32)
+                .push_arg(true),
+        );
+        let mut expected = selector.to_vec();
+        7u32.encode_to(&mut expected);
+        true.encode_to(&mut expected);
+        SpanContext::current().encode_to(&mut expected);
+        assert_eq!(input.encode(), expected);
+
+        let input = with_span_context(ExecutionInput::new(Selector::new(selector)));
+        let mut expected = selector.to_vec();
+        SpanContext::current().encode_to(&mut expected);
+        assert_eq!(input.encode(), expected);
+    }
+}
@@ -0,0 +1,57 @@
+//! Correlation IDs grouping the records of one call.
+//!
+//! An ID is generated on the first [`crate::enter_span`] of a call and included in every
+//! following record, so the lines of one user action can be grouped in the worker logs even when
+//! they interleave with other calls.
+
+use alloc::string::String;
+use core::fmt::Write;
+use scale::Encode;
+
+use crate::local::call_local;
+
+call_local! {
+    static CORRELATION_ID: Option<String> = None;
+}
+
+/// The correlation ID of the current call, if a span was entered or an ID was imported.
+pub fn correlation_id() -> Option<String> {
+    CORRELATION_ID.with(|id| id.borrow().clone())
+}
+
+/// Use `id` for the following records of the current call, e.g. one received from a caller.
+pub fn set_correlation_id(id: String) {
+    CORRELATION_ID.with(|current| *current.borrow_mut() = Some(id));
+}
+
+/// Generate the ID of the call unless it has one.
+pub(crate) fn ensure() {
+    if CORRELATION_ID.with(|id| id.borrow().is_none()) {
+        set_correlation_id(generate());
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn reset() {
+    CORRELATION_ID.with(|id| *id.borrow_mut() = None);
+}
+
+/// 16 hex digits derived with `pink::vrf` from the caller and the current time.
+fn generate() -> String {
+    #[cfg(feature = "std")]
+    if crate::testing::installed() {
+        return crate::testing::next_correlation_id();
+    }
+    let now = if pink::ext().is_in_transaction() {
+        ink::env::block_timestamp::<pink::PinkEnvironment>()
+    } else {
+        pink::ext().untrusted_millis_since_unix_epoch()
+    };
+    let caller = ink::env::caller::<pink::PinkEnvironment>();
+    let random = pink::vrf(&(caller, now).encode());
+    let mut id = String::with_capacity(16);
+    for byte in random.iter().take(8) {
+        let _ = write!(id, "{byte:02x}");
+    }
+    id
+}
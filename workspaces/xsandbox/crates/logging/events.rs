@@ -0,0 +1,138 @@
+//! Persistence of transaction records as ink events.
+//!
+//! Records logged with `pink::ext().log` stay in the worker's log buffer. Once enabled with
+//! [`enable_tx_events`], records logged in transactions are also passed as [`LogRecord`]s to an
+//! emitter, so important diagnostics of state changes are kept in the event stream. Events
+//! cost gas, so only enable them for the levels which matter.
+//!
+//! ink encodes events through the contract's own event enum and lists them in its metadata, so
+//! the contract declares the event and emits it:
+//!
+//! ```ignore
+//! #[ink(event)]
+//! pub struct Logged {
+//!     record: logging::LogRecord,
+//! }
+//!
+//! #[ink(message)]
+//! pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+//!     logging::enable_tx_events(logging::Level::Warn, |record| {
+//!         Self::env().emit_event(Logged { record })
+//!     });
+//!     ...
+//! }
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use scale::{Decode, Encode};
+
+use crate::{local::call_local, Level, Record};
+
+/// A record logged in a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct LogRecord {
+    pub level: Level,
+    pub tags: Vec<String>,
+    pub correlation_id: Option<String>,
+    pub message: String,
+}
+
+call_local! {
+    static EMITTER: Option<(Level, fn(LogRecord))> = None;
+}
+
+/// Also pass the records up to `max_level` logged in the current transaction to `emit`, which
+/// emits them as an event of the contract.
+pub fn enable_tx_events(max_level: impl Into<Level>, emit: fn(LogRecord)) {
+    EMITTER.with(|emitter| *emitter.borrow_mut() = Some((max_level.into(), emit)));
+}
+
+pub fn disable_tx_events() {
+    EMITTER.with(|emitter| *emitter.borrow_mut() = None);
+}
+
+/// The emitter of records at `level`, if they are emitted as events.
+pub(crate) fn emitter(level: Level) -> Option<fn(LogRecord)> {
+    let (max, emit) = EMITTER.with(|emitter| *emitter.borrow())?;
+    (level <= max && pink::ext().is_in_transaction()).then_some(emit)
+}
+
+pub(crate) fn emit(emit: fn(LogRecord), record: &Record) {
+    emit(LogRecord {
+        level: record.level,
+        tags: record.tags.to_vec(),
+        correlation_id: record.correlation_id.map(Into::into),
+        message: record.message.into(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pink::chain_extension::mock;
+
+    // ink's generated code checks cfgs which this crate does not declare.
+    #[allow(unexpected_cfgs)]
+    #[ink::contract(env = pink::PinkEnvironment)]
+    mod logged {
+        use crate::{Level, LogRecord};
+
+        #[ink(event)]
+        pub struct Logged {
+            pub record: LogRecord,
+        }
+
+        #[ink(storage)]
+        pub struct Contract {}
+
+        impl Contract {
+            #[ink(constructor)]
+            pub fn new() -> Self {
+                Self {}
+            }
+
+            #[ink(message)]
+            pub fn run(&mut self) {
+                crate::enable_tx_events(Level::Warn, |record| {
+                    Self::env().emit_event(Logged { record })
+                });
+                crate::warn!("low balance");
+                crate::info!("not emitted");
+                crate::disable_tx_events();
+                crate::error!("not emitted");
+            }
+        }
+    }
+
+    #[ink::test]
+    fn emits_records_as_contract_events() {
+        crate::testing::install();
+        mock::mock_is_in_transaction(|| true);
+        logged::Contract::new().run();
+
+        let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        let mut data = &events[0].data[..];
+        // The index of `Logged` in the contract's event enum comes first.
+        assert_eq!(u8::decode(&mut data), Ok(0));
+        let record = LogRecord::decode(&mut data).unwrap();
+        assert!(data.is_empty());
+        assert_eq!(
+            record,
+            LogRecord {
+                level: Level::Warn,
+                tags: Vec::new(),
+                correlation_id: crate::correlation_id(),
+                message: "low balance".into(),
+            }
+        );
+    }
+
+    #[ink::test]
+    fn skips_queries() {
+        crate::testing::install();
+        logged::Contract::new().run();
+        assert_eq!(ink::env::test::recorded_events().count(), 0);
+    }
+}
@@ -0,0 +1,245 @@
+//! Extensions of `Result` and `Option` logging their failures, and error context.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Debug, Display, Formatter};
+use core::panic::Location;
+use scale::{Decode, Encode};
+
+use crate::Level;
+
+/// An extension for Result<T, E> to log error conveniently.
+///
+/// The records get the caller's source file, e.g. `src/http.rs`, as target, since the module
+/// path is only known to macros. Per-target [`crate::filter`] directives match it like a module.
+pub trait ResultExt {
+    type Ok;
+    type Err;
+
+    /// Log the the error message with `pink::error!` with a tip `msg` in front if the Result is Err.
+    #[track_caller]
+    fn log_err(self, msg: &str) -> Self
+    where
+        Self: Sized;
+
+    /// Same as [`ResultExt::log_err`], but at the given level.
+    #[track_caller]
+    fn log_err_at(self, level: impl Into<Level>, msg: &str) -> Self
+    where
+        Self: Sized;
+
+    /// Log `msg` at Info level if the Result is Ok.
+    #[track_caller]
+    fn log_ok(self, msg: &str) -> Self
+    where
+        Self: Sized;
+
+    /// Log the error like [`ResultExt::log_err`] and convert it with `f`, typically into the
+    /// contract's own error type.
+    #[track_caller]
+    fn log_and_map_err<F, E2>(self, msg: &str, f: F) -> Result<Self::Ok, E2>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Err) -> E2;
+}
+
+impl<T, E: Debug> ResultExt for Result<T, E> {
+    type Ok = T;
+    type Err = E;
+
+    #[track_caller]
+    fn log_err(self, msg: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.log_err_at(Level::Error, msg)
+    }
+
+    #[track_caller]
+    fn log_err_at(self, level: impl Into<Level>, msg: &str) -> Self
+    where
+        Self: Sized,
+    {
+        if let Err(err) = &self {
+            log_caller(level.into(), format_args!("{msg}: {err:?}"));
+        }
+        self
+    }
+
+    #[track_caller]
+    fn log_ok(self, msg: &str) -> Self
+    where
+        Self: Sized,
+    {
+        if self.is_ok() {
+            log_caller(Level::Info, format_args!("{msg}"));
+        }
+        self
+    }
+
+    #[track_caller]
+    fn log_and_map_err<F, E2>(self, msg: &str, f: F) -> Result<T, E2>
+    where
+        Self: Sized,
+        F: FnOnce(E) -> E2,
+    {
+        self.log_err(msg).map_err(f)
+    }
+}
+
+/// An extension for Option<T> to log a missing value conveniently.
+///
+/// Like with [`ResultExt`], the target of the records is the caller's source file.
+pub trait OptionExt {
+    /// Log `msg` at Error level if the Option is None.
+    #[track_caller]
+    fn log_none(self, msg: &str) -> Self
+    where
+        Self: Sized;
+}
+
+impl<T> OptionExt for Option<T> {
+    #[track_caller]
+    fn log_none(self, msg: &str) -> Self
+    where
+        Self: Sized,
+    {
+        if self.is_none() {
+            log_caller(Level::Error, format_args!("{msg}"));
+        }
+        self
+    }
+}
+
+/// Log with the caller's source file as target.
+#[track_caller]
+// `STATIC_MAX_LEVEL` is 0 with `max_level_off`
+#[allow(clippy::absurd_extreme_comparisons)]
+fn log_caller(level: Level, args: core::fmt::Arguments<'_>) {
+    if u8::from(level) <= crate::STATIC_MAX_LEVEL {
+        crate::log_target(level, Location::caller().file(), &[], args);
+    }
+}
+
+/// A contract error together with human-readable context of where it happened.
+///
+/// It is SCALE encodable like the error itself, so it can be returned from messages and shown
+/// by clients. The context is ordered from the innermost to the outermost.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct WithContext<E> {
+    pub error: E,
+    pub context: Vec<String>,
+}
+
+impl<E> From<E> for WithContext<E> {
+    fn from(error: E) -> Self {
+        Self {
+            error,
+            context: Vec::new(),
+        }
+    }
+}
+
+/// Displays as `outer: inner: error`.
+impl<E: Debug> Display for WithContext<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{context}: ")?;
+        }
+        write!(f, "{:?}", self.error)
+    }
+}
+
+/// Marks contract error types that [`Context::context`] can be attached to.
+///
+/// ```ignore
+/// impl logging::ContextError for Error {}
+/// ```
+pub trait ContextError {}
+
+/// Attaching context to errors, chaining onto the context already attached.
+///
+/// ```ignore
+/// fn fetch(&self) -> Result<u128, WithContext<Error>> {
+///     let resp = self.request().context("requesting balance")?;
+///     parse(resp).context("parsing balance")
+/// }
+/// ```
+pub trait Context<T, E> {
+    fn context(self, context: &str) -> Result<T, WithContext<E>>;
+}
+
+impl<T, E: ContextError> Context<T, E> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, WithContext<E>> {
+        self.map_err(|error| WithContext {
+            error,
+            context: vec![context.into()],
+        })
+    }
+}
+
+impl<T, E> Context<T, E> for Result<T, WithContext<E>> {
+    fn context(self, context: &str) -> Result<T, WithContext<E>> {
+        self.map_err(|mut error| {
+            error.context.push(context.into());
+            error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{captured_logs, install};
+
+    #[test]
+    fn logs_with_caller_file_as_target() {
+        install();
+
+        let _ = Err::<(), _>("boom").log_err("request failed");
+        let _ = Ok::<_, ()>(1).log_ok("request sent");
+        let _ = None::<u8>.log_none("missing");
+        let _ = Ok::<_, ()>(1).log_err("not logged");
+
+        let logs: Vec<_> = captured_logs()
+            .into_iter()
+            .map(|log| (log.level, log.target, log.message))
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                (
+                    Level::Error,
+                    file!().into(),
+                    "request failed: \"boom\"".into()
+                ),
+                (Level::Info, file!().into(), "request sent".into()),
+                (Level::Error, file!().into(), "missing".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_error_after_logging() {
+        install();
+
+        let res: Result<(), u8> = Err("boom").log_and_map_err("failed", |_| 7);
+        assert_eq!(res, Err(7));
+        assert_eq!(captured_logs()[0].message, "failed: \"boom\"");
+    }
+
+    #[test]
+    fn context_chains_outward() {
+        #[derive(Debug)]
+        struct Error;
+        impl ContextError for Error {}
+
+        let res: Result<(), WithContext<Error>> = Err(Error)
+            .context("parsing balance")
+            .context("fetching balance");
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "fetching balance: parsing balance: Error"
+        );
+    }
+}
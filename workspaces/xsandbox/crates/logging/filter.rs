@@ -0,0 +1,185 @@
+//! Runtime level filter kept in the pink cache.
+//!
+//! The filter lets the verbosity of a deployed contract be raised or lowered without
+//! redeploying it. The cache is only readable in queries, so transactions log everything
+//! allowed by [`crate::STATIC_MAX_LEVEL`].
+
+use alloc::{string::String, vec::Vec};
+use pink::chain_extension::StorageQuotaExceeded;
+use scale::{Decode, Encode};
+
+use crate::local::call_local;
+
+const CACHE_KEY: &[u8] = b"logging::filter";
+
+/// Maximum levels of records, globally and per target.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Filter {
+    /// Level of targets without an override.
+    pub default: u8,
+    /// Overrides by target (module path). The longest matching prefix wins.
+    pub targets: Vec<(String, u8)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            default: 5,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Filter {
+    /// Parse a filter spec such as `info,my_contract::http=debug`.
+    ///
+    /// Levels are given by name (`off`, `error`, `warn`, `info`, `debug`, `trace`) or number.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut filter = Self::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().into(), parse_level(level.trim())?)),
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        Some(filter)
+    }
+
+    /// Maximum level of records from `target`.
+    pub fn level_for(&self, target: &str) -> u8 {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| matches_target(prefix, target))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    pub fn enabled(&self, level: u8, target: &str) -> bool {
+        level <= self.level_for(target)
+    }
+}
+
+fn parse_level(level: &str) -> Option<u8> {
+    Some(match level {
+        "off" => 0,
+        "error" => 1,
+        "warn" => 2,
+        "info" => 3,
+        "debug" => 4,
+        "trace" => 5,
+        _ => level.parse().ok()?,
+    })
+}
+
+/// Whether `prefix` equals `target` or is one of its parent modules, or directories for
+/// targets which are source files.
+fn matches_target(prefix: &str, target: &str) -> bool {
+    match target.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::") || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+call_local! {
+    static FILTER: Option<Filter> = None;
+}
+
+/// Run `f` with the filter in effect, loaded from the cache on first use in a call.
+fn with_current<R>(f: impl FnOnce(&Filter) -> R) -> R {
+    FILTER.with(|filter| {
+        f(filter.borrow_mut().get_or_insert_with(|| {
+            pink::ext()
+                .cache_get(CACHE_KEY)
+                .and_then(|raw| Filter::decode(&mut &raw[..]).ok())
+                .unwrap_or_default()
+        }))
+    })
+}
+
+/// The filter in effect, loaded from the cache on first use in a call.
+pub fn current() -> Filter {
+    with_current(Filter::clone)
+}
+
+/// Whether records at `level` from `target` pass the runtime filter.
+pub fn enabled(level: u8, target: &str) -> bool {
+    with_current(|filter| filter.enabled(level, target))
+}
+
+/// Store `filter` in the cache of the calling contract.
+///
+/// The helper does no access control, contracts are expected to expose it through an
+/// admin-only message.
+pub fn set_filter(filter: &Filter) -> Result<(), StorageQuotaExceeded> {
+    pink::ext().cache_set(CACHE_KEY, &filter.encode())?;
+    FILTER.with(|current| *current.borrow_mut() = Some(filter.clone()));
+    Ok(())
+}
+
+/// Remove the stored filter, so all records allowed at compile time are logged again.
+pub fn clear_filter() {
+    pink::ext().cache_remove(CACHE_KEY);
+    FILTER.with(|current| *current.borrow_mut() = None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_spec() {
+        let filter = Filter::parse(" warn, my_contract::http=debug,my_contract=0 ").unwrap();
+        assert_eq!(filter.default, 2);
+        assert_eq!(
+            filter.targets,
+            [("my_contract::http".into(), 4), ("my_contract".into(), 0)]
+        );
+        assert_eq!(Filter::parse(""), Some(Filter::default()));
+        assert_eq!(Filter::parse("verbose"), None);
+        assert_eq!(Filter::parse("my_contract=loud"), None);
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let filter = Filter::parse("info,my_contract=error,my_contract::http=trace").unwrap();
+        assert_eq!(filter.level_for("my_contract"), 1);
+        assert_eq!(filter.level_for("my_contract::storage"), 1);
+        assert_eq!(filter.level_for("my_contract::http"), 5);
+        assert_eq!(filter.level_for("my_contract::http::retry"), 5);
+        assert_eq!(filter.level_for("my_contract_v2"), 3);
+        assert_eq!(filter.level_for("my_contract::httpx"), 1);
+        assert_eq!(filter.level_for(""), 3);
+        assert!(filter.enabled(1, "my_contract"));
+        assert!(!filter.enabled(2, "my_contract"));
+    }
+
+    #[test]
+    fn matches_source_files() {
+        let filter = Filter::parse("info,src=warn,src/http.rs=debug").unwrap();
+        assert_eq!(filter.level_for("src/http.rs"), 4);
+        assert_eq!(filter.level_for("src/storage.rs"), 2);
+        assert_eq!(filter.level_for("srcs/lib.rs"), 3);
+    }
+
+    #[test]
+    fn stored_filter_applies() {
+        crate::testing::install();
+        assert!(enabled(5, "my_contract"));
+
+        set_filter(&Filter::parse("my_contract=warn").unwrap()).unwrap();
+        assert!(enabled(2, "my_contract::http"));
+        assert!(!enabled(3, "my_contract::http"));
+        assert!(enabled(5, "other"));
+
+        // the next call loads the filter from the cache
+        FILTER.with(|filter| *filter.borrow_mut() = None);
+        assert!(!enabled(3, "my_contract"));
+
+        clear_filter();
+        assert!(enabled(5, "my_contract"));
+    }
+}
@@ -0,0 +1,254 @@
+//! Shipping of records to a log collector over HTTP.
+
+use alloc::{string::String, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{fields, Record, Sink};
+
+/// Body format of the requests sent by [`HttpSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    /// A JSON array of records.
+    Json,
+    /// One JSON record per line.
+    Ndjson,
+}
+
+/// A [`Sink`] POSTing batches of records to a collector endpoint with `pink::http_post!`.
+///
+/// Records are serialized like the structured records logged to the worker and sent when the
+/// outermost span is exited, on [`crate::flush`], or once `batch_size` records are pending.
+/// HTTP requests are not available in transactions, so records logged there are skipped.
+///
+/// ```ignore
+/// #[ink(message)]
+/// pub fn get_price(&self) -> u128 {
+///     logging::add_sink(
+///         logging::HttpSink::new(&self.collector_url).header("Authorization", &self.token),
+///     );
+///     let _span = logging::enter_span("get_price");
+///     // records of the span are posted when it is exited
+/// }
+/// ```
+pub struct HttpSink {
+    endpoint: String,
+    format: BodyFormat,
+    headers: Vec<(String, String)>,
+    batch_size: usize,
+    pending: RefCell<Vec<String>>,
+}
+
+impl HttpSink {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            format: BodyFormat::Json,
+            headers: Vec::new(),
+            batch_size: 100,
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn format(mut self, format: BodyFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Send `name: value` with each request, e.g. the credentials of the collector.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Send the pending records once `batch_size` of them are collected.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn body(&self, records: &[String]) -> Vec<u8> {
+        let mut body = String::new();
+        match self.format {
+            BodyFormat::Json => {
+                body.push('[');
+                body.push_str(&records.join(","));
+                body.push(']');
+            }
+            BodyFormat::Ndjson => {
+                for record in records {
+                    body.push_str(record);
+                    body.push('\n');
+                }
+            }
+        }
+        body.into_bytes()
+    }
+}
+
+impl Sink for HttpSink {
+    fn log(&self, record: &Record) {
+        if pink::ext().is_in_transaction() {
+            return;
+        }
+        let json = fields::to_json(
+            record.level,
+            record.correlation_id,
+            record.tags,
+            record.fields,
+            record.message,
+        );
+        let full = {
+            let mut pending = self.pending.borrow_mut();
+            pending.push(json);
+            pending.len() >= self.batch_size
+        };
+        if full {
+            self.flush();
+        }
+    }
+
+    fn flush(&self) {
+        let records = core::mem::take(&mut *self.pending.borrow_mut());
+        if records.is_empty() {
+            return;
+        }
+        let content_type = match self.format {
+            BodyFormat::Json => "application/json",
+            BodyFormat::Ndjson => "application/x-ndjson",
+        };
+        let mut headers = vec![("Content-Type".into(), content_type.into())];
+        headers.extend(self.headers.iter().cloned());
+        let response = pink::http_post!(self.endpoint.as_str(), self.body(&records), headers);
+        if !(200..300).contains(&response.status_code) {
+            // Logged directly, as logging through the sinks would come back here.
+            pink::ext().log(
+                crate::Level::Warn.into(),
+                &format!(
+                    "failed to ship {} records: HTTP {}",
+                    records.len(),
+                    response.status_code
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{collected_requests, install, mock_collector};
+    use pink::chain_extension::mock;
+
+    const ENDPOINT: &str = "https://logs.example.com/ingest";
+
+    fn bodies() -> Vec<String> {
+        collected_requests()
+            .iter()
+            .map(|request| request.body_str().into())
+            .collect()
+    }
+
+    #[test]
+    fn posts_json_batches() {
+        install();
+        mock_collector(200);
+        crate::add_sink(
+            HttpSink::new(ENDPOINT)
+                .header("Authorization", "Bearer abc")
+                .batch_size(2),
+        );
+        crate::info!("one");
+        assert!(collected_requests().is_empty());
+        crate::warn!(n = 2; "two");
+        crate::error!("three");
+
+        let requests = collected_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, ENDPOINT);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(
+            requests[0].headers,
+            [
+                ("Content-Type".into(), "application/json".into()),
+                ("Authorization".into(), "Bearer abc".into()),
+            ]
+        );
+        assert_eq!(
+            bodies(),
+            [concat!(
+                r#"[{"level":"info","tags":[],"fields":{},"message":"one"},"#,
+                r#"{"level":"warn","tags":[],"fields":{"n":2},"message":"two"}]"#,
+            )]
+        );
+
+        crate::flush();
+        assert_eq!(
+            bodies()[1],
+            r#"[{"level":"error","tags":[],"fields":{},"message":"three"}]"#
+        );
+        crate::flush();
+        assert_eq!(collected_requests().len(), 2);
+    }
+
+    #[test]
+    fn posts_ndjson_at_the_outermost_span() {
+        install();
+        mock_collector(204);
+        crate::add_sink(HttpSink::new(ENDPOINT).format(BodyFormat::Ndjson));
+        {
+            let _span = crate::enter_span("outer");
+            crate::info!("one");
+            {
+                let _span = crate::enter_span("inner");
+                crate::info!("two");
+            }
+            assert!(collected_requests().is_empty());
+        }
+        let requests = collected_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].headers[0],
+            ("Content-Type".into(), "application/x-ndjson".into())
+        );
+        assert_eq!(
+            bodies(),
+            [concat!(
+                r#"{"level":"info","cid":"0000000000000001","tags":["outer"],"fields":{},"message":"one"}"#,
+                "\n",
+                r#"{"level":"info","cid":"0000000000000001","tags":["outer","inner"],"fields":{},"message":"two"}"#,
+                "\n",
+            )]
+        );
+    }
+
+    #[test]
+    fn skips_transactions() {
+        install();
+        mock_collector(200);
+        mock::mock_is_in_transaction(|| true);
+        crate::add_sink(HttpSink::new(ENDPOINT));
+        crate::info!("one");
+        crate::flush();
+        assert!(collected_requests().is_empty());
+        assert_eq!(crate::testing::captured_logs().len(), 1);
+    }
+
+    #[test]
+    fn warns_when_rejected() {
+        install();
+        mock_collector(500);
+        let logged: std::rc::Rc<core::cell::RefCell<Vec<(u8, String)>>> = Default::default();
+        let sink = logged.clone();
+        mock::mock_log(move |level, message| sink.borrow_mut().push((level, message.into())));
+        crate::set_sinks(vec![Box::new(HttpSink::new(ENDPOINT))]);
+        crate::info!("one");
+        crate::flush();
+        assert_eq!(
+            *logged.borrow(),
+            [(
+                crate::Level::Warn.into(),
+                "failed to ship 1 records: HTTP 500".into()
+            )]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Levels of log records.
+
+use core::fmt::{Display, Formatter};
+use scale::{Decode, Encode};
+
+/// Severity of a log record, from the most severe.
+///
+/// The discriminants are the levels passed to `pink::ext().log`, and raw `u8` levels are
+/// still accepted wherever a level is expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+#[repr(u8)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+/// Levels below 1 are treated as Error and above 5 as Trace.
+impl From<u8> for Level {
+    fn from(level: u8) -> Self {
+        match level {
+            0 | 1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+}
+
+impl From<Level> for u8 {
+    fn from(level: Level) -> Self {
+        level as u8
+    }
+}
+
+impl PartialEq<u8> for Level {
+    fn eq(&self, other: &u8) -> bool {
+        *self as u8 == *other
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_raw_levels() {
+        assert_eq!(Level::from(0), Level::Error);
+        assert_eq!(Level::from(3), Level::Info);
+        assert_eq!(Level::from(9), Level::Trace);
+        assert_eq!(u8::from(Level::Warn), 2);
+        assert!(Level::Error < Level::Debug);
+        assert_eq!(format!("{}", Level::Debug), "debug");
+    }
+}
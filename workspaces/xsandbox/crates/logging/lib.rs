@@ -2,6 +2,9 @@
 
 #[macro_use]
 extern crate alloc;
+// Lets the tests use the derives, which refer to `::logging`.
+#[cfg(test)]
+extern crate self as logging;
 
 use alloc::{
     fmt::Arguments,
@@ -12,25 +15,44 @@ use alloc::{
 use ink::primitives::AccountId;
 use pink_macro::driver;
 
-/// An extension for Result<T, E> to log error conveniently.
-pub trait ResultExt {
-    /// Log the the error message with `pink::error!` with a tip `msg` in front if the Result is Err.
-    fn log_err(self, msg: &str) -> Self
-    where
-        Self: Sized;
-}
-
-impl<T, E: core::fmt::Debug> ResultExt for Result<T, E> {
-    fn log_err(self, msg: &str) -> Self
-    where
-        Self: Sized,
-    {
-        if let Err(err) = &self {
-            error!("{msg}: {err:?}");
-        }
-        self
-    }
-}
+#[cfg(feature = "log-bridge")]
+pub use bridge::{init, PinkLog};
+pub use buffer::{disable_buffering, enable_buffering};
+pub use context::{import_span_context, with_span_context, PushSpanContext, SpanContext};
+pub use correlation::{correlation_id, set_correlation_id};
+pub use events::{disable_tx_events, enable_tx_events, LogRecord};
+pub use ext::{Context, ContextError, OptionExt, ResultExt, WithContext};
+pub use fields::{Field, ToValue, Value};
+pub use http_sink::{BodyFormat, HttpSink};
+pub use level::Level;
+pub use logging_macro::{instrument, RedactedDebug};
+pub use panic::ExpectExt;
+#[doc(hidden)]
+pub use rate::Callsite;
+pub use redact::{redact, redact_query_key};
+pub use secret::{Secret, MASK};
+pub use sink::{add_sink, flush, reset_sinks, set_sinks, PinkSink, Record, Sink};
+
+#[cfg(feature = "log-bridge")]
+mod bridge;
+mod buffer;
+mod context;
+mod correlation;
+mod events;
+mod ext;
+mod fields;
+pub mod filter;
+mod http_sink;
+mod level;
+mod local;
+pub mod metrics;
+mod panic;
+mod rate;
+mod redact;
+mod secret;
+mod sink;
+#[cfg(feature = "std")]
+pub mod testing;
 
 #[driver]
 #[ink::trait_definition]
@@ -43,67 +65,349 @@ pub trait TagStack {
     fn tags(&self) -> Vec<String>;
 }
 
+local::call_local! {
+    static LOCAL_TAGS: Vec<String> = Vec::new();
+}
+
+/// Where the tags of a [`Span`] live.
+enum Bag {
+    /// The `TagStack` driver, used in queries.
+    Driver(TagStackRef),
+    /// The contract-local stack, used in transactions where the driver can not be called.
+    Local,
+}
+
+/// Start time of a timed [`Span`].
+struct Timer {
+    start: u64,
+    threshold_ms: u64,
+}
+
+/// A log span, exited when dropped.
+///
+/// `Span::default()` is a placeholder which neither tags records nor counts as entered, so
+/// dropping it does not flush the buffer.
 #[derive(Default)]
 pub struct Span {
-    bag: Option<TagStackRef>,
+    name: String,
+    bag: Option<Bag>,
+    timer: Option<Timer>,
+    /// Whether the span was counted by `buffer::enter`.
+    entered: bool,
+}
+
+impl Span {
+    /// Log the duration of the span at Debug level when it is exited.
+    ///
+    /// Timing relies on `untrusted_millis_since_unix_epoch`, so it is skipped in transactions.
+    pub fn timed(self) -> Self {
+        self.timed_over(0)
+    }
+
+    /// Same as [`Span::timed`], but only report spans lasting at least `threshold_ms`.
+    pub fn timed_over(mut self, threshold_ms: u64) -> Self {
+        if !pink::ext().is_in_transaction() {
+            self.timer = Some(Timer {
+                start: pink::ext().untrusted_millis_since_unix_epoch(),
+                threshold_ms,
+            });
+        }
+        self
+    }
 }
 
 impl Drop for Span {
     fn drop(&mut self) {
-        if let Some(mut bag) = self.bag.take() {
-            bag.pop_tag();
+        if let Some(timer) = self.timer.take() {
+            let elapsed = pink::ext()
+                .untrusted_millis_since_unix_epoch()
+                .saturating_sub(timer.start);
+            if elapsed >= timer.threshold_ms {
+                debug!("span {} finished in {} ms", self.name, elapsed);
+            }
+        }
+        // Exited before popping the tag, so the records reported and flushed when leaving the
+        // outermost span are still tagged with it.
+        if self.entered {
+            buffer::exit();
+        }
+        match self.bag.take() {
+            Some(Bag::Driver(mut bag)) => bag.pop_tag(),
+            Some(Bag::Local) => {
+                LOCAL_TAGS.with(|tags| tags.borrow_mut().pop());
+            }
+            None => {}
         }
     }
 }
 
 /// Enter a log span. The span will be exited when the returned value is dropped.
+///
+/// Use `enter_span("name").timed()` to also log how long the span lasted.
+///
+/// In transactions the tags are kept in a contract-local stack living for the duration of
+/// the call, otherwise in the `TagStack` driver.
+///
+/// The first span of a call generates the [`correlation_id`] of its records. Records buffered
+/// with [`enable_buffering`] are flushed when the outermost span is exited.
 pub fn enter_span(span: &str) -> Span {
-    if pink::ext().is_in_transaction() {
-        return Span::default();
+    let name = span.to_string();
+    correlation::ensure();
+    buffer::enter();
+    if use_local_tags() {
+        LOCAL_TAGS.with(|tags| tags.borrow_mut().push(name.clone()));
+        return Span {
+            name,
+            bag: Some(Bag::Local),
+            timer: None,
+            entered: true,
+        };
     }
     let Some(mut bag) = TagStackRef::instance() else {
-        return Span::default();
+        return Span {
+            name,
+            bag: None,
+            timer: None,
+            entered: true,
+        };
     };
-    bag.push_tag(span.to_string());
-    Span { bag: Some(bag) }
+    bag.push_tag(name.clone());
+    Span {
+        name,
+        bag: Some(Bag::Driver(bag)),
+        timer: None,
+        entered: true,
+    }
 }
 
-pub fn tagged_prefix() -> Option<String> {
+/// Whether span tags are kept in the contract-local stack rather than the `TagStack` driver.
+fn use_local_tags() -> bool {
+    #[cfg(feature = "std")]
+    if testing::installed() {
+        return true;
+    }
+    pink::ext().is_in_transaction()
+}
+
+fn current_tags() -> Option<Vec<String>> {
+    if use_local_tags() {
+        let tags = LOCAL_TAGS.with(|tags| tags.borrow().clone());
+        return (!tags.is_empty()).then_some(tags);
+    }
     let bag = TagStackRef::instance()?;
-    Some(bag.tags().join(","))
+    Some(bag.tags())
 }
 
-pub fn log(level: u8, args: Arguments<'_>) {
-    let message = match tagged_prefix() {
-        Some(prefix) => {
-            format!("[{}]: {}", prefix, args)
+pub fn tagged_prefix() -> Option<String> {
+    current_tags().map(|tags| tags.join(","))
+}
+
+/// The most verbose level compiled in, selected with the `max_level_*` features and, in builds
+/// without debug assertions, the `release_max_level_*` features. `0` disables logging entirely.
+///
+/// Macro invocations above this level compile to nothing.
+pub const STATIC_MAX_LEVEL: u8 = static_max_level();
+
+const fn static_max_level() -> u8 {
+    if !cfg!(debug_assertions) {
+        if cfg!(feature = "release_max_level_off") {
+            return 0;
+        } else if cfg!(feature = "release_max_level_error") {
+            return 1;
+        } else if cfg!(feature = "release_max_level_warn") {
+            return 2;
+        } else if cfg!(feature = "release_max_level_info") {
+            return 3;
+        } else if cfg!(feature = "release_max_level_debug") {
+            return 4;
+        } else if cfg!(feature = "release_max_level_trace") {
+            return 5;
         }
-        None => {
-            format!("{}", args)
+    }
+    if cfg!(feature = "max_level_off") {
+        0
+    } else if cfg!(feature = "max_level_error") {
+        1
+    } else if cfg!(feature = "max_level_warn") {
+        2
+    } else if cfg!(feature = "max_level_info") {
+        3
+    } else if cfg!(feature = "max_level_debug") {
+        4
+    } else {
+        5
+    }
+}
+
+pub fn log(level: impl Into<Level>, args: Arguments<'_>) {
+    log_target(level, "", &[], args)
+}
+
+/// Log a record carrying structured fields.
+pub fn log_with_fields(level: impl Into<Level>, fields: &[Field], args: Arguments<'_>) {
+    log_target(level, "", fields, args)
+}
+
+/// Log a record of `target` (usually the module path), subject to the runtime [`filter`].
+///
+/// Records with fields are serialized as a single JSON line holding the level, the correlation
+/// ID, the current tags, the fields and the message, so they can be filtered by field rather
+/// than by text.
+/// Records without fields keep the plain `cid=<correlation id> [tags]: message` form, leaving
+/// out the parts which are not set.
+///
+/// Credentials found in the message and string fields are masked, see [`redact`]. The record
+/// is passed to the configured [`Sink`]s and, when enabled with [`enable_tx_events`], to the
+/// contract's event emitter in transactions.
+pub fn log_target(level: impl Into<Level>, target: &str, fields: &[Field], args: Arguments<'_>) {
+    let level = level.into();
+    if !filter::enabled(level.into(), target) {
+        return;
+    }
+    let tags = current_tags();
+    let correlation_id = correlation_id();
+    let message = redact(&args.to_string());
+    let fields: Vec<Field> = fields.iter().map(Field::redacted).collect();
+    let line = if fields.is_empty() {
+        match (&correlation_id, &tags) {
+            (Some(id), Some(tags)) => format!("cid={} [{}]: {}", id, tags.join(","), message),
+            (Some(id), None) => format!("cid={}: {}", id, message),
+            (None, Some(tags)) => format!("[{}]: {}", tags.join(","), message),
+            (None, None) => message.clone(),
         }
+    } else {
+        fields::to_json(
+            level,
+            correlation_id.as_deref(),
+            tags.as_deref().unwrap_or_default(),
+            &fields,
+            &message,
+        )
     };
-    pink::ext().log(level, &message);
+    let record = Record {
+        level,
+        target,
+        correlation_id: correlation_id.as_deref(),
+        tags: tags.as_deref().unwrap_or_default(),
+        fields: &fields,
+        message: &message,
+        line: &line,
+    };
+    if let Some(emit) = events::emitter(level) {
+        events::emit(emit, &record);
+    }
+    sink::dispatch(&record);
 }
 
 /// The `log!` macro allows you to log messages with specific logging levels in pink contract.
 ///
 /// It is a flexible macro that uses a provided log level (trace, debug, info, warn, error),
 /// followed by a format string and an optional list of arguments to generate the final log message.
+///
+/// The format string may be preceded by a list of `key = value` fields terminated with `;`.
+/// Values are captured through [`ToValue`], or through `Display` / `Debug` when prefixed
+/// with `%` / `?`.
+///
+/// A leading `every = n;` logs only every n-th record of the invocation and `once;` only the
+/// first one. The number of records left out is logged when the outermost span is exited:
+///
+/// ```ignore
+/// for item in items {
+///     logging::info!(every = 100; "processing {}", item.id);
+/// }
+/// ```
 #[macro_export]
 macro_rules! log {
-    ($level: expr, $($arg:tt)+) => {{ $crate::log($level, ::core::format_args!($($arg)+)) }}
+    ($level: expr, every = $n: expr; $($arg:tt)+) => {{
+        let level: u8 = $crate::Level::from($level).into();
+        if $crate::log_enabled!(level) {
+            static CALLSITE: $crate::Callsite = $crate::Callsite::new();
+            if CALLSITE.sample($n, level) {
+                $crate::log!(level, $($arg)+)
+            }
+        }
+    }};
+    ($level: expr, once; $($arg:tt)+) => {{
+        let level: u8 = $crate::Level::from($level).into();
+        if $crate::log_enabled!(level) {
+            static CALLSITE: $crate::Callsite = $crate::Callsite::new();
+            if CALLSITE.once(level) {
+                $crate::log!(level, $($arg)+)
+            }
+        }
+    }};
+    ($level: expr, $key: ident = $($rest:tt)+) => {{
+        let level: u8 = $crate::Level::from($level).into();
+        if $crate::log_enabled!(level) {
+            $crate::__log_fields!(@ level, [] $key = $($rest)+)
+        }
+    }};
+    ($level: expr, $($arg:tt)+) => {{
+        let level: u8 = $crate::Level::from($level).into();
+        if $crate::log_enabled!(level) {
+            $crate::log_target(level, ::core::module_path!(), &[], ::core::format_args!($($arg)+))
+        }
+    }};
+}
+
+/// Check whether records at `level` from the calling module would be logged, considering both
+/// [`STATIC_MAX_LEVEL`] and the runtime [`filter`].
+///
+/// Useful to skip expensive preparation of records which would be discarded anyway.
+#[macro_export]
+macro_rules! log_enabled {
+    ($level: expr) => {{
+        let level: u8 = $crate::Level::from($level).into();
+        level <= $crate::STATIC_MAX_LEVEL && $crate::filter::enabled(level, ::core::module_path!())
+    }};
+}
+
+/// Collects the `key = value` fields of `log!` one by one.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_fields {
+    (@ $level: expr, [$($f: expr),*] $key: ident = %$val: expr, $($rest:tt)+) => {
+        $crate::__log_fields!(@ $level, [$($f,)* $crate::Field::display(::core::stringify!($key), &$val)] $($rest)+)
+    };
+    (@ $level: expr, [$($f: expr),*] $key: ident = %$val: expr; $($arg:tt)+) => {
+        $crate::log_target($level, ::core::module_path!(), &[$($f,)* $crate::Field::display(::core::stringify!($key), &$val)], ::core::format_args!($($arg)+))
+    };
+    (@ $level: expr, [$($f: expr),*] $key: ident = ?$val: expr, $($rest:tt)+) => {
+        $crate::__log_fields!(@ $level, [$($f,)* $crate::Field::debug(::core::stringify!($key), &$val)] $($rest)+)
+    };
+    (@ $level: expr, [$($f: expr),*] $key: ident = ?$val: expr; $($arg:tt)+) => {
+        $crate::log_target($level, ::core::module_path!(), &[$($f,)* $crate::Field::debug(::core::stringify!($key), &$val)], ::core::format_args!($($arg)+))
+    };
+    (@ $level: expr, [$($f: expr),*] $key: ident = $val: expr, $($rest:tt)+) => {
+        $crate::__log_fields!(@ $level, [$($f,)* $crate::Field::new(::core::stringify!($key), &$val)] $($rest)+)
+    };
+    (@ $level: expr, [$($f: expr),*] $key: ident = $val: expr; $($arg:tt)+) => {
+        $crate::log_target($level, ::core::module_path!(), &[$($f,)* $crate::Field::new(::core::stringify!($key), &$val)], ::core::format_args!($($arg)+))
+    };
 }
 
 /// Same as `info!` but at Error level.
 #[macro_export(local_inner_macros)]
 macro_rules! error {
-    ($($arg:tt)+) => {{ log!(1, $($arg)+) }}
+    ($($arg:tt)+) => {{ log!($crate::Level::Error, $($arg)+) }}
 }
 
 /// Same as `info!` but at Warn level.
 #[macro_export(local_inner_macros)]
 macro_rules! warn {
-    ($($arg:tt)+) => {{ log!(2, $($arg)+) }}
+    ($($arg:tt)+) => {{ log!($crate::Level::Warn, $($arg)+) }}
+}
+
+/// Same as `error!` but only logs the first record of the invocation in a call.
+#[macro_export(local_inner_macros)]
+macro_rules! error_once {
+    ($($arg:tt)+) => {{ log!($crate::Level::Error, once; $($arg)+) }}
+}
+
+/// Same as `warn!` but only logs the first record of the invocation in a call.
+#[macro_export(local_inner_macros)]
+macro_rules! warn_once {
+    ($($arg:tt)+) => {{ log!($crate::Level::Warn, once; $($arg)+) }}
 }
 
 /// Macro `info!` logs messages at the Info level in pink contract.
@@ -124,13 +428,175 @@ macro_rules! warn {
 ///
 /// The above example would log "This is an information message." and
 /// "The answer is 42." at the Info level.
+///
+/// Structured fields:
+///
+/// ```ignore
+/// logging::info!(account = ?caller, amount = value; "transfer done");
+/// ```
+///
+/// The above example would log
+/// `{"level":"info","tags":[],"fields":{"account":"...","amount":100},"message":"transfer done"}`.
 #[macro_export(local_inner_macros)]
 macro_rules! info {
-    ($($arg:tt)+) => {{ log!(3, $($arg)+) }}
+    ($($arg:tt)+) => {{ log!($crate::Level::Info, $($arg)+) }}
 }
 
 /// Same as `info!` but at Debug level.
 #[macro_export(local_inner_macros)]
 macro_rules! debug {
-    ($($arg:tt)+) => {{ log!(4, $($arg)+) }}
+    ($($arg:tt)+) => {{ log!($crate::Level::Debug, $($arg)+) }}
+}
+
+/// Same as `info!` but at Trace level.
+#[macro_export(local_inner_macros)]
+macro_rules! trace {
+    ($($arg:tt)+) => {{ log!($crate::Level::Trace, $($arg)+) }}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{captured_logs, install};
+
+    fn lines() -> Vec<String> {
+        captured_logs().into_iter().map(|log| log.line).collect()
+    }
+
+    #[test]
+    fn formats_lines() {
+        install();
+        info!("plain");
+        set_correlation_id("c1".into());
+        info!("with id");
+        {
+            let _span = enter_span("outer");
+            let _inner = enter_span("inner");
+            info!("tagged {}", 1);
+            info!(amount = 5, to = %"bob"; "sent");
+        }
+        assert_eq!(
+            lines(),
+            [
+                "plain",
+                "cid=c1: with id",
+                "cid=c1 [outer,inner]: tagged 1",
+                r#"{"level":"info","cid":"c1","tags":["outer","inner"],"fields":{"amount":5,"to":"bob"},"message":"sent"}"#,
+            ]
+        );
+        assert_eq!(captured_logs()[0].target, module_path!());
+    }
+
+    #[test]
+    fn redacts_messages_and_fields() {
+        install();
+        warn!(url = "https://u:p@host"; "token Bearer abc");
+        let log = &captured_logs()[0];
+        assert_eq!(log.message, "token Bearer ***");
+        assert_eq!(log.fields, [crate::Field::new("url", &"https://***@host")]);
+    }
+
+    #[instrument(args(account, amount), err)]
+    fn transfer(account: &str, amount: u64) -> Result<u64, &'static str> {
+        info!("moving funds");
+        if amount == 0 {
+            return Err("zero amount");
+        }
+        Ok(amount)
+    }
+
+    #[instrument(name = "fetch", context = context)]
+    fn fetch(context: SpanContext) -> Option<Vec<String>> {
+        current_tags()
+    }
+
+    #[test]
+    fn instrument_wraps_in_a_span() {
+        install();
+        assert_eq!(transfer("alice", 5), Ok(5));
+        assert_eq!(transfer("bob", 0), Err("zero amount"));
+
+        let logs = captured_logs();
+        assert_eq!(logs.len(), 5);
+        assert!(logs.iter().all(|log| log.tags == ["transfer"]));
+        assert_eq!(logs[0].level, Level::Debug);
+        assert_eq!(logs[0].message, "enter");
+        assert_eq!(
+            logs[0].fields,
+            [
+                Field::debug("account", &"alice"),
+                Field::debug("amount", &5u64),
+            ]
+        );
+        assert_eq!(logs[1].message, "moving funds");
+        assert_eq!(logs[2].fields[0], Field::debug("account", &"bob"));
+        assert_eq!(logs[4].level, Level::Error);
+        assert_eq!(logs[4].message, "transfer: \"zero amount\"");
+        assert_eq!(current_tags(), None);
+    }
+
+    #[test]
+    fn instrument_continues_the_caller_span() {
+        install();
+        let context = SpanContext {
+            tags: vec!["caller".into()],
+            correlation_id: Some("c1".into()),
+        };
+        assert_eq!(fetch(context), Some(vec!["caller".into(), "fetch".into()]));
+        assert_eq!(correlation_id().as_deref(), Some("c1"));
+        assert_eq!(current_tags(), None);
+    }
+
+    #[test]
+    fn trace_and_log_enabled_follow_the_filter() {
+        install();
+        assert!(log_enabled!(Level::Trace));
+        trace!("details");
+        assert_eq!(captured_logs()[0].level, Level::Trace);
+        assert_eq!(captured_logs()[0].message, "details");
+
+        let filter = filter::Filter::parse(&format!("trace,{}=info", module_path!())).unwrap();
+        filter::set_filter(&filter).unwrap();
+        assert!(log_enabled!(Level::Info));
+        assert!(!log_enabled!(Level::Debug));
+        trace!("filtered");
+        debug!("filtered");
+        info!("kept");
+        let messages: Vec<_> = captured_logs().into_iter().map(|log| log.message).collect();
+        assert_eq!(messages, ["details", "kept"]);
+        filter::clear_filter();
+    }
+
+    /// Mock the clock to advance by `step_ms` on every read.
+    fn mock_clock(step_ms: u64) {
+        let now = std::rc::Rc::new(core::cell::Cell::new(1_000));
+        pink::chain_extension::mock::mock_untrusted_millis_since_unix_epoch(move || {
+            now.set(now.get() + step_ms);
+            now.get()
+        });
+    }
+
+    #[test]
+    fn timed_spans_report_over_threshold() {
+        install();
+        mock_clock(30);
+        drop(enter_span("fast").timed_over(50));
+        drop(enter_span("slow").timed_over(30));
+        drop(enter_span("any").timed());
+        let logs = captured_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].level, Level::Debug);
+        assert_eq!(logs[0].message, "span slow finished in 30 ms");
+        assert_eq!(logs[0].tags, ["slow"]);
+        assert_eq!(logs[1].message, "span any finished in 30 ms");
+    }
+
+    #[test]
+    fn timed_spans_are_skipped_in_transactions() {
+        install();
+        mock_clock(30);
+        pink::chain_extension::mock::mock_is_in_transaction(|| true);
+        drop(enter_span("tx").timed());
+        assert!(captured_logs().is_empty());
+    }
 }
@@ -0,0 +1,38 @@
+//! State living for the duration of a single contract call.
+//!
+//! Every contract call runs in a fresh instance, so plain statics are call-local on chain.
+//! Off-chain tests (`std`) run on several threads, hence thread locals there.
+
+/// Declare call-local statics, accessed with `NAME.with(|cell| ...)`.
+macro_rules! call_local {
+    ($(static $name: ident: $t: ty = $init: expr;)+) => {
+        #[cfg(feature = "std")]
+        std::thread_local! {
+            $(static $name: core::cell::RefCell<$t> = const { core::cell::RefCell::new($init) };)+
+        }
+        $(
+            #[cfg(not(feature = "std"))]
+            static $name: crate::local::CallLocal<$t> = crate::local::CallLocal::new($init);
+        )+
+    };
+}
+
+pub(crate) use call_local;
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct CallLocal<T>(core::cell::RefCell<T>);
+
+// Contracts are executed single threaded.
+#[cfg(not(feature = "std"))]
+unsafe impl<T> Sync for CallLocal<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> CallLocal<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(core::cell::RefCell::new(value))
+    }
+
+    pub(crate) fn with<R>(&'static self, f: impl FnOnce(&core::cell::RefCell<T>) -> R) -> R {
+        f(&self.0)
+    }
+}
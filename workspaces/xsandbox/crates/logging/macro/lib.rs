@@ -0,0 +1,238 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, Ident, ItemFn, LitStr, Result,
+    ReturnType,
+};
+
+/// Options of the `#[instrument]` attribute.
+#[derive(Default)]
+struct Options {
+    name: Option<LitStr>,
+    args: Vec<Ident>,
+    err: bool,
+    context: Option<Ident>,
+}
+
+/// Wrap the body of a function (typically an `#[ink(message)]`) in a log span.
+///
+/// Supported options:
+///
+/// - `name = "..."`: the span name, defaults to the function name.
+/// - `args(a, b)`: log the given arguments at Debug level when entering the span.
+/// - `err`: log the returned `Result` with `ResultExt::log_err` if it is an `Err`.
+/// - `context = arg`: continue the caller's span with the `logging::SpanContext` argument `arg`
+///   before entering the span, see `logging::with_span_context`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[ink(message)]
+/// #[logging::instrument(args(account), err)]
+/// pub fn get_balance(&self, account: String) -> Result<u128> {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn instrument(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = Options::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            options.name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("args") {
+            meta.parse_nested_meta(|arg| {
+                let ident = arg.path.require_ident()?;
+                options.args.push(ident.clone());
+                Ok(())
+            })
+        } else if meta.path.is_ident("err") {
+            options.err = true;
+            Ok(())
+        } else if meta.path.is_ident("context") {
+            options.context = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported instrument option"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+    let func = parse_macro_input!(item as ItemFn);
+    match patch(options, func) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn patch(options: Options, func: ItemFn) -> Result<TokenStream2> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = func;
+    if sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            sig.asyncness,
+            "instrument does not support async functions",
+        ));
+    }
+    let name = options
+        .name
+        .unwrap_or_else(|| LitStr::new(&sig.ident.to_string(), sig.ident.span()));
+    let output = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let log_args = if options.args.is_empty() {
+        quote!()
+    } else {
+        let args = &options.args;
+        quote! {
+            ::logging::debug!(#(#args = ?#args),*; "enter");
+        }
+    };
+    let ret = if options.err {
+        quote!(::logging::ResultExt::log_err(__logging_ret, #name))
+    } else {
+        quote!(__logging_ret)
+    };
+    let import_context = match &options.context {
+        Some(context) => quote! {
+            let __logging_context = ::logging::import_span_context(#context);
+        },
+        None => quote!(),
+    };
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            #import_context
+            let __logging_span = ::logging::enter_span(#name);
+            #log_args
+            #[allow(clippy::redundant_closure_call)]
+            let __logging_ret = (move || -> #output #block)();
+            #ret
+        }
+    })
+}
+
+/// Derive `Debug`, printing the fields marked with `#[redact]` as `logging::MASK`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(logging::RedactedDebug)]
+/// pub struct Credentials {
+///     access_key: String,
+///     #[redact]
+///     secret_key: String,
+/// }
+/// ```
+#[proc_macro_derive(RedactedDebug, attributes(redact))]
+pub fn derive_redacted_debug(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match redacted_debug(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn redacted_debug(mut input: DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, fmt) = fmt_fields(&name, &data.fields);
+            quote! {
+                let #ident #pattern = self;
+                #fmt
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                let (pattern, fmt) = fmt_fields(&variant_ident.to_string(), &variant.fields);
+                quote!(#ident::#variant_ident #pattern => { #fmt })
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "RedactedDebug does not support unions",
+            ))
+        }
+    };
+    let params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::core::fmt::Debug));
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// The destructuring pattern of `fields` and the statement formatting them.
+fn fmt_fields(name: &str, fields: &Fields) -> (TokenStream2, TokenStream2) {
+    let redacted = |attrs: &[syn::Attribute]| attrs.iter().any(|a| a.path().is_ident("redact"));
+    match fields {
+        Fields::Named(fields) => {
+            let mut bindings = Vec::new();
+            let mut entries = Vec::new();
+            for field in &fields.named {
+                let ident = field.ident.as_ref().expect("named field");
+                let label = ident.to_string();
+                if redacted(&field.attrs) {
+                    bindings.push(quote!(#ident: _));
+                    entries
+                        .push(quote!(.field(#label, &::core::format_args!("{}", ::logging::MASK))));
+                } else {
+                    let binding = Ident::new(&format!("__field_{label}"), ident.span());
+                    bindings.push(quote!(#ident: #binding));
+                    entries.push(quote!(.field(#label, #binding)));
+                }
+            }
+            (
+                quote!({ #(#bindings),* }),
+                quote!(f.debug_struct(#name) #(#entries)* .finish()),
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let mut bindings = Vec::new();
+            let mut entries = Vec::new();
+            for (i, field) in fields.unnamed.iter().enumerate() {
+                if redacted(&field.attrs) {
+                    bindings.push(quote!(_));
+                    entries.push(quote!(.field(&::core::format_args!("{}", ::logging::MASK))));
+                } else {
+                    let binding =
+                        Ident::new(&format!("__field{i}"), proc_macro2::Span::call_site());
+                    bindings.push(quote!(#binding));
+                    entries.push(quote!(.field(#binding)));
+                }
+            }
+            (
+                quote!(( #(#bindings),* )),
+                quote!(f.debug_tuple(#name) #(#entries)* .finish()),
+            )
+        }
+        Fields::Unit => (quote!(), quote!(f.write_str(#name))),
+    }
+}
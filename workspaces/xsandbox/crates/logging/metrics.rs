@@ -0,0 +1,320 @@
+//! Counters, gauges and histograms kept in the pink cache.
+//!
+//! The cache is private to each contract, so the metrics of a contract accumulate across its
+//! queries until the cache entry is evicted or [`reset`]. The cache can not be read in
+//! transactions, so updates made there are skipped.
+//!
+//! A query loads the metrics on the first update and changes its own copy. Inside spans the copy
+//! is written back once by [`crate::flush`], done when the outermost span is exited; outside of
+//! spans each update is written through. Queries run concurrently do not see each other's
+//! updates: the copy written last wins, dropping the updates of the others.
+//!
+//! ```ignore
+//! logging::metrics::counter!("http.requests");
+//! if response.status_code != 200 {
+//!     logging::metrics::counter!("http.failures", 1);
+//! }
+//! logging::metrics::histogram!("http.body_bytes", response.body.len() as u64);
+//! ```
+//!
+//! Export them with a message implementing [`MetricsSource`], or log them with [`dump`].
+
+use alloc::{string::String, vec::Vec};
+use scale::{Decode, Encode};
+
+use crate::local::call_local;
+
+pub use crate::{
+    __metrics_counter as counter, __metrics_gauge as gauge, __metrics_histogram as histogram,
+};
+
+const CACHE_KEY: &[u8] = b"logging::metrics";
+
+/// Summary of recorded values, with power of two buckets.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Histogram {
+    pub count: u64,
+    pub sum: u64,
+    pub min: u64,
+    pub max: u64,
+    /// Number of values by bucket `i`, holding the values in `[2^(i-1), 2^i)` (`0` for 0).
+    pub buckets: Vec<(u8, u64)>,
+}
+
+impl Histogram {
+    fn record(&mut self, value: u64) {
+        self.min = if self.count == 0 {
+            value
+        } else {
+            self.min.min(value)
+        };
+        self.max = self.max.max(value);
+        self.count += 1;
+        self.sum = self.sum.saturating_add(value);
+        let bucket = (64 - value.leading_zeros()) as u8;
+        match self.buckets.iter_mut().find(|(b, _)| *b == bucket) {
+            Some((_, count)) => *count += 1,
+            None => {
+                let pos = self.buckets.partition_point(|(b, _)| *b < bucket);
+                self.buckets.insert(pos, (bucket, 1));
+            }
+        }
+    }
+}
+
+/// All metrics of the contract.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Metrics {
+    pub counters: Vec<(String, u64)>,
+    pub gauges: Vec<(String, i64)>,
+    pub histograms: Vec<(String, Histogram)>,
+}
+
+/// Look up the entry of `name`, inserting a default one if needed.
+fn entry<'a, T: Default>(entries: &'a mut Vec<(String, T)>, name: &str) -> &'a mut T {
+    let pos = match entries.iter().position(|(n, _)| n == name) {
+        Some(pos) => pos,
+        None => {
+            entries.push((name.into(), T::default()));
+            entries.len() - 1
+        }
+    };
+    &mut entries[pos].1
+}
+
+/// Exports the metrics of a contract.
+///
+/// ```ignore
+/// impl logging::metrics::MetricsSource for MyContract {
+///     #[ink(message)]
+///     fn metrics(&self) -> logging::metrics::Metrics {
+///         logging::metrics::snapshot()
+///     }
+/// }
+/// ```
+#[ink::trait_definition]
+pub trait MetricsSource {
+    #[ink(message)]
+    fn metrics(&self) -> Metrics;
+}
+
+call_local! {
+    static METRICS: Option<Metrics> = None;
+    static DIRTY: bool = false;
+}
+
+fn load() -> Metrics {
+    pink::ext()
+        .cache_get(CACHE_KEY)
+        .and_then(|raw| Metrics::decode(&mut &raw[..]).ok())
+        .unwrap_or_default()
+}
+
+fn update(f: impl FnOnce(&mut Metrics)) {
+    if pink::ext().is_in_transaction() {
+        return;
+    }
+    METRICS.with(|metrics| f(metrics.borrow_mut().get_or_insert_with(load)));
+    DIRTY.with(|dirty| *dirty.borrow_mut() = true);
+    if !crate::buffer::in_span() {
+        save();
+    }
+}
+
+/// Write the metrics updated in the current call to the cache.
+pub(crate) fn save() {
+    if !DIRTY.with(|dirty| dirty.replace(false)) {
+        return;
+    }
+    let Some(encoded) = METRICS.with(|metrics| metrics.borrow().as_ref().map(Encode::encode))
+    else {
+        return;
+    };
+    // Metrics are best effort, dropping the update is fine when over the quota.
+    let _ = pink::ext().cache_set(CACHE_KEY, &encoded);
+}
+
+/// Add `value` to the counter `name`.
+pub fn increment_counter(name: &str, value: u64) {
+    update(|metrics| {
+        let counter = entry(&mut metrics.counters, name);
+        *counter = counter.saturating_add(value);
+    });
+}
+
+/// Set the gauge `name` to `value`.
+pub fn set_gauge(name: &str, value: i64) {
+    update(|metrics| *entry(&mut metrics.gauges, name) = value);
+}
+
+/// Record `value` in the histogram `name`.
+pub fn record_histogram(name: &str, value: u64) {
+    update(|metrics| entry(&mut metrics.histograms, name).record(value));
+}
+
+/// The current metrics of the contract.
+pub fn snapshot() -> Metrics {
+    METRICS.with(|metrics| metrics.borrow_mut().get_or_insert_with(load).clone())
+}
+
+/// Remove all metrics.
+pub fn reset() {
+    pink::ext().cache_remove(CACHE_KEY);
+    METRICS.with(|metrics| *metrics.borrow_mut() = Some(Metrics::default()));
+    DIRTY.with(|dirty| *dirty.borrow_mut() = false);
+}
+
+/// Log all metrics as one record at `level`.
+pub fn dump(level: impl Into<crate::Level>) {
+    use core::fmt::Write;
+
+    let metrics = snapshot();
+    let mut out = String::from("metrics:");
+    for (name, value) in &metrics.counters {
+        let _ = write!(out, " {name}={value}");
+    }
+    for (name, value) in &metrics.gauges {
+        let _ = write!(out, " {name}={value}");
+    }
+    for (name, h) in &metrics.histograms {
+        let _ = write!(
+            out,
+            " {name}={{count={},sum={},min={},max={}}}",
+            h.count, h.sum, h.min, h.max
+        );
+    }
+    crate::log(level, format_args!("{out}"));
+}
+
+/// Add to a counter, by 1 unless a value is given.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __metrics_counter {
+    ($name: expr) => {
+        $crate::metrics::increment_counter($name, 1)
+    };
+    ($name: expr, $value: expr) => {
+        $crate::metrics::increment_counter($name, $value)
+    };
+}
+
+/// Set a gauge.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __metrics_gauge {
+    ($name: expr, $value: expr) => {
+        $crate::metrics::set_gauge($name, $value)
+    };
+}
+
+/// Record a value in a histogram.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __metrics_histogram {
+    ($name: expr, $value: expr) => {
+        $crate::metrics::record_histogram($name, $value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pink::chain_extension::mock;
+
+    fn cached() -> Option<Metrics> {
+        pink::ext()
+            .cache_get(CACHE_KEY)
+            .map(|raw| Metrics::decode(&mut &raw[..]).unwrap())
+    }
+
+    #[test]
+    fn histogram_buckets_by_power_of_two() {
+        let mut histogram = Histogram::default();
+        for value in [5, 0, 1, 7, 4, 1024] {
+            histogram.record(value);
+        }
+        assert_eq!(
+            histogram,
+            Histogram {
+                count: 6,
+                sum: 1041,
+                min: 0,
+                max: 1024,
+                buckets: vec![(0, 1), (1, 1), (3, 3), (11, 1)],
+            }
+        );
+    }
+
+    #[test]
+    fn writes_the_cache_at_the_outermost_span() {
+        crate::testing::install();
+        {
+            let _span = crate::enter_span("outer");
+            counter!("requests");
+            {
+                let _span = crate::enter_span("inner");
+                counter!("requests", 2);
+                gauge!("queue", -3);
+            }
+            histogram!("bytes", 10);
+            assert_eq!(cached(), None);
+        }
+        let metrics = Metrics {
+            counters: vec![("requests".into(), 3)],
+            gauges: vec![("queue".into(), -3)],
+            histograms: vec![(
+                "bytes".into(),
+                Histogram {
+                    count: 1,
+                    sum: 10,
+                    min: 10,
+                    max: 10,
+                    buckets: vec![(4, 1)],
+                },
+            )],
+        };
+        assert_eq!(cached(), Some(metrics.clone()));
+        assert_eq!(snapshot(), metrics);
+
+        reset();
+        assert_eq!(cached(), None);
+        counter!("requests");
+        crate::flush();
+        assert_eq!(cached().unwrap().counters, [("requests".into(), 1)]);
+    }
+
+    #[test]
+    fn writes_through_outside_of_spans() {
+        crate::testing::install();
+        counter!("requests");
+        assert_eq!(cached().unwrap().counters, [("requests".into(), 1)]);
+        gauge!("queue", 4);
+        assert_eq!(cached().unwrap().gauges, [("queue".into(), 4)]);
+    }
+
+    #[test]
+    fn skips_transactions() {
+        crate::testing::install();
+        mock::mock_is_in_transaction(|| true);
+        counter!("requests");
+        crate::flush();
+        assert_eq!(cached(), None);
+        assert_eq!(snapshot(), Metrics::default());
+    }
+
+    #[test]
+    fn dumps_all_metrics() {
+        crate::testing::install();
+        counter!("requests");
+        gauge!("queue", 2);
+        histogram!("bytes", 3);
+        histogram!("bytes", 5);
+        dump(crate::Level::Info);
+        assert_eq!(
+            crate::testing::captured_logs()[0].message,
+            "metrics: requests=1 queue=2 bytes={count=2,sum=8,min=3,max=5}"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Logging of failed unwraps together with the current span tags.
+//!
+//! ink 4 defines the panic handler of contracts itself and offers no way to replace it, so a
+//! panic can not be logged once it happens. Unwrap with [`ExpectExt::log_expect`] instead, which
+//! logs the failure before panicking.
+
+use core::{fmt::Debug, panic::Location};
+
+/// Unwrapping which logs the failure, with its location and span tags, before panicking.
+pub trait ExpectExt<T> {
+    /// Same as `expect`, but logs the failure at Error level first.
+    fn log_expect(self, msg: &str) -> T;
+}
+
+impl<T, E: Debug> ExpectExt<T> for Result<T, E> {
+    #[track_caller]
+    fn log_expect(self, msg: &str) -> T {
+        match self {
+            Ok(value) => value,
+            Err(err) => {
+                let location = Location::caller();
+                crate::error!("panicked at {location}: {msg}: {err:?}");
+                crate::flush();
+                panic!("{msg}: {err:?}")
+            }
+        }
+    }
+}
+
+impl<T> ExpectExt<T> for Option<T> {
+    #[track_caller]
+    fn log_expect(self, msg: &str) -> T {
+        match self {
+            Some(value) => value,
+            None => {
+                let location = Location::caller();
+                crate::error!("panicked at {location}: {msg}");
+                crate::flush();
+                panic!("{msg}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{captured_logs, install};
+    use std::panic::catch_unwind;
+
+    #[test]
+    fn logs_failed_unwraps_with_tags() {
+        install();
+        let _span = crate::enter_span("store");
+        let line = line!() + 1;
+        let result = catch_unwind(|| Err::<u8, _>("full").log_expect("write failed"));
+        assert!(result.is_err());
+        let line_none = line!() + 1;
+        let result = catch_unwind(|| None::<u8>.log_expect("missing"));
+        assert!(result.is_err());
+
+        let logs = captured_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].level, crate::Level::Error);
+        assert_eq!(logs[0].tags, ["store"]);
+        let location = format!("panicked at {}:{line}:", file!());
+        assert!(logs[0].message.starts_with(&location));
+        assert!(logs[0].message.ends_with(": write failed: \"full\""));
+        let location = format!("panicked at {}:{line_none}:", file!());
+        assert!(logs[1].message.starts_with(&location));
+        assert!(logs[1].message.ends_with(": missing"));
+    }
+
+    #[test]
+    fn passes_values_through() {
+        install();
+        assert_eq!(Ok::<_, ()>(1).log_expect("unreachable"), 1);
+        assert_eq!(Some(2).log_expect("unreachable"), 2);
+        assert!(captured_logs().is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+//! Per-callsite rate limiting of records.
+//!
+//! `log!(level, every = n; ...)` logs only every n-th record of a callsite and
+//! `log!(level, once; ...)` only the first one. The records left out are counted and reported
+//! as `suppressed N records` when the outermost span is exited.
+//!
+//! Hits are counted in call-local state rather than in the callsite's static, so off-chain tests
+//! do not share them: each runs on its own thread and [`crate::testing::install`] resets them.
+
+use alloc::collections::BTreeMap;
+
+use crate::local::call_local;
+
+/// A rate limited macro invocation, identified by the address of its static.
+#[doc(hidden)]
+pub struct Callsite {
+    // Not zero-sized, so that every callsite has its own address.
+    _id: u8,
+}
+
+impl Callsite {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self { _id: 0 }
+    }
+
+    /// Whether the current hit is the first of each `n`, counting it as suppressed otherwise.
+    pub fn sample(&'static self, n: u32, level: u8) -> bool {
+        let key = self as *const Self as usize;
+        let hit = HITS.with(|hits| {
+            let mut hits = hits.borrow_mut();
+            let count = hits.entry(key).or_insert(0);
+            let hit = *count;
+            *count = count.saturating_add(1);
+            hit
+        });
+        let pass = matches!(hit % n.max(1), 0);
+        if !pass {
+            suppressed(level);
+        }
+        pass
+    }
+
+    /// Whether this is the first hit, counting it as suppressed otherwise.
+    pub fn once(&'static self, level: u8) -> bool {
+        self.sample(u32::MAX, level)
+    }
+}
+
+/// Number and most severe level of the records suppressed since the last report.
+struct Suppressed {
+    count: u32,
+    level: u8,
+}
+
+call_local! {
+    static HITS: BTreeMap<usize, u32> = BTreeMap::new();
+    static SUPPRESSED: Option<Suppressed> = None;
+}
+
+fn suppressed(level: u8) {
+    SUPPRESSED.with(|suppressed| {
+        let mut suppressed = suppressed.borrow_mut();
+        match suppressed.as_mut() {
+            Some(suppressed) => {
+                suppressed.count += 1;
+                suppressed.level = suppressed.level.min(level);
+            }
+            None => *suppressed = Some(Suppressed { count: 1, level }),
+        }
+    });
+}
+
+/// Log how many records were suppressed, at the most severe level among them.
+pub(crate) fn report() {
+    let Some(suppressed) = SUPPRESSED.with(|suppressed| suppressed.borrow_mut().take()) else {
+        return;
+    };
+    crate::log(
+        suppressed.level,
+        format_args!("suppressed {} records", suppressed.count),
+    );
+}
+
+/// Forget the hits and suppressed records, for [`crate::testing::install`].
+#[cfg(feature = "std")]
+pub(crate) fn reset() {
+    HITS.with(|hits| hits.borrow_mut().clear());
+    SUPPRESSED.with(|suppressed| *suppressed.borrow_mut() = None);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{captured_logs, install};
+
+    fn messages() -> Vec<String> {
+        captured_logs().into_iter().map(|log| log.message).collect()
+    }
+
+    fn warn_once(i: u32) {
+        crate::warn_once!("first {}", i);
+    }
+
+    #[test]
+    fn samples_every_nth_hit() {
+        install();
+        {
+            let _span = crate::enter_span("outer");
+            for i in 0..5 {
+                crate::info!(every = 2; "item {}", i);
+            }
+            {
+                let _span = crate::enter_span("inner");
+            }
+            assert_eq!(messages(), ["item 0", "item 2", "item 4"]);
+        }
+        assert_eq!(
+            messages(),
+            ["item 0", "item 2", "item 4", "suppressed 2 records"]
+        );
+        assert_eq!(captured_logs()[3].tags, ["outer"]);
+        assert_eq!(captured_logs()[3].level, crate::Level::Info);
+    }
+
+    #[test]
+    fn reports_most_severe_suppressed_level() {
+        install();
+        {
+            let _span = crate::enter_span("outer");
+            for i in 0..3 {
+                warn_once(i);
+                crate::error_once!("error {}", i);
+            }
+        }
+        let logs = captured_logs();
+        assert_eq!(messages(), ["first 0", "error 0", "suppressed 4 records"]);
+        assert_eq!(logs[2].level, crate::Level::Error);
+    }
+
+    #[test]
+    fn install_resets_callsites() {
+        install();
+        warn_once(0);
+        warn_once(1);
+        assert_eq!(messages(), ["first 0"]);
+
+        install();
+        warn_once(2);
+        assert_eq!(messages(), ["first 2"]);
+    }
+}
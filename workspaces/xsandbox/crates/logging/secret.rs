@@ -0,0 +1,111 @@
+//! Values masked when formatted, so they do not end up in logs.
+
+use core::fmt::{Debug, Display, Formatter, Result};
+use scale::{Decode, Encode};
+
+/// What is printed in place of a secret value.
+pub const MASK: &str = "***";
+
+/// A value whose `Debug` and `Display` print [`MASK`] instead of the value.
+///
+/// The value is SCALE encoded as is, so `Secret` can wrap contract storage fields such as API
+/// keys. Use [`crate::RedactedDebug`] to mask fields without changing their type.
+#[derive(Default, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Access the secret value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(MASK)
+    }
+}
+
+impl<T> Display for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(MASK)
+    }
+}
+
+#[cfg(test)]
+// The redacted fields of the test types are never read.
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[derive(crate::RedactedDebug)]
+    struct Credentials {
+        f: String,
+        #[redact]
+        secret_key: String,
+    }
+
+    #[derive(crate::RedactedDebug)]
+    struct Pair(u8, #[redact] u8);
+
+    #[derive(crate::RedactedDebug)]
+    enum Auth<T> {
+        Basic {
+            user: T,
+            #[redact]
+            password: T,
+        },
+        Bearer(#[redact] T),
+        None,
+    }
+
+    #[test]
+    fn masks_secrets() {
+        let secret = Secret::new("key");
+        assert_eq!(format!("{secret:?}"), MASK);
+        assert_eq!(format!("{secret}"), MASK);
+        assert_eq!(*secret.expose(), "key");
+    }
+
+    #[test]
+    fn derive_masks_redacted_fields() {
+        let credentials = Credentials {
+            f: "access".into(),
+            secret_key: "secret".into(),
+        };
+        assert_eq!(
+            format!("{credentials:?}"),
+            r#"Credentials { f: "access", secret_key: *** }"#
+        );
+        assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(1, ***)");
+        let basic = Auth::Basic {
+            user: "alice",
+            password: "hunter2",
+        };
+        assert_eq!(
+            format!("{basic:?}"),
+            r#"Basic { user: "alice", password: *** }"#
+        );
+        assert_eq!(format!("{:?}", Auth::Bearer("token")), "Bearer(***)");
+        assert_eq!(format!("{:?}", Auth::<u8>::None), "None");
+    }
+}
@@ -0,0 +1,87 @@
+//! Destinations of log records.
+//!
+//! Records go to `pink::ext().log` through [`PinkSink`] unless other sinks are configured for
+//! the current call with [`add_sink`] or [`set_sinks`].
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::{buffer, local::call_local, Field, Level};
+
+/// A log record passed to the sinks.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub level: Level,
+    /// Usually the module path of the macro invocation, empty when not known.
+    pub target: &'a str,
+    pub correlation_id: Option<&'a str>,
+    pub tags: &'a [String],
+    pub fields: &'a [Field],
+    pub message: &'a str,
+    /// The record formatted as a single line, as passed to `pink::ext().log`.
+    pub line: &'a str,
+}
+
+/// A destination of log records.
+pub trait Sink {
+    fn log(&self, record: &Record);
+
+    /// Emit the records kept back by the sink, if any.
+    fn flush(&self) {}
+}
+
+/// Sends records to the worker log server with `pink::ext().log`, subject to
+/// [`crate::enable_buffering`].
+pub struct PinkSink;
+
+impl Sink for PinkSink {
+    fn log(&self, record: &Record) {
+        buffer::emit(record.level.into(), record.line.into());
+    }
+
+    fn flush(&self) {
+        buffer::flush();
+    }
+}
+
+call_local! {
+    static SINKS: Option<Vec<Box<dyn Sink>>> = None;
+}
+
+/// Also send the records of the current call to `sink`.
+pub fn add_sink(sink: impl Sink + 'static) {
+    SINKS.with(|sinks| {
+        sinks
+            .borrow_mut()
+            .get_or_insert_with(|| vec![Box::new(PinkSink)])
+            .push(Box::new(sink));
+    });
+}
+
+/// Send the records of the current call to `sinks` only.
+pub fn set_sinks(new: Vec<Box<dyn Sink>>) {
+    SINKS.with(|sinks| *sinks.borrow_mut() = Some(new));
+}
+
+/// Send the records of the current call to [`PinkSink`] only, dropping the other sinks.
+pub fn reset_sinks() {
+    SINKS.with(|sinks| *sinks.borrow_mut() = None);
+}
+
+pub(crate) fn dispatch(record: &Record) {
+    SINKS.with(|sinks| match sinks.borrow().as_deref() {
+        Some(sinks) => sinks.iter().for_each(|sink| sink.log(record)),
+        None => PinkSink.log(record),
+    });
+}
+
+/// Flush all sinks, e.g. the records kept back with [`crate::enable_buffering`], and write the
+/// updated [`crate::metrics`] to the cache.
+///
+/// Done automatically when the outermost span is exited.
+pub fn flush() {
+    SINKS.with(|sinks| match sinks.borrow().as_deref() {
+        Some(sinks) => sinks.iter().for_each(|sink| sink.flush()),
+        None => PinkSink.flush(),
+    });
+    crate::metrics::save();
+}
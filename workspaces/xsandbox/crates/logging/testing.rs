@@ -0,0 +1,167 @@
+//! Capture of log records in off-chain unit tests.
+//!
+//! ```ignore
+//! #[ink::test]
+//! fn logs_error() {
+//!     logging::testing::install();
+//!
+//!     let contract = MyContract::new();
+//!     assert!(contract.do_something().is_err());
+//!
+//!     let logs = logging::testing::captured_logs();
+//!     assert_eq!(logs[0].level, logging::Level::Error);
+//!     assert_eq!(logs[0].message, "do_something failed: InvalidInput");
+//! }
+//! ```
+
+use std::{cell::RefCell, collections::BTreeMap, string::String, vec::Vec};
+
+use pink::chain_extension::mock;
+
+use crate::{Field, Level, PinkSink, Record, Sink};
+
+/// A record logged while capturing was installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedLog {
+    pub level: Level,
+    pub target: String,
+    pub tags: Vec<String>,
+    pub fields: Vec<Field>,
+    /// The correlation ID of the call, see [`crate::correlation_id`].
+    pub correlation_id: Option<String>,
+    pub message: String,
+    /// The line as passed to `pink::ext().log`.
+    pub line: String,
+}
+
+std::thread_local! {
+    static CAPTURED: RefCell<Option<Vec<CapturedLog>>> = const { RefCell::new(None) };
+    static COLLECTED: RefCell<Vec<CollectedRequest>> = const { RefCell::new(Vec::new()) };
+    static NEXT_CORRELATION_ID: RefCell<u64> = const { RefCell::new(0) };
+}
+
+/// Mock the chain extension calls used by the crate and start capturing records.
+///
+/// Records are captured by a sink added next to [`PinkSink`], replacing the configured sinks.
+/// Span tags are kept in the contract-local stack, so no `TagStack` driver is needed. The
+/// pink cache is backed by an in-memory map; call this before registering other mocks to let
+/// them take precedence.
+///
+/// Correlation IDs are numbered from `0000000000000001` instead of being random, and the hits
+/// of `every = n;` and `once;` callsites start over.
+pub fn install() {
+    mock::mock_log(|_, _| {});
+    mock::mock_is_in_transaction(|| false);
+    mock::mock_untrusted_millis_since_unix_epoch(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default()
+    });
+    let cache: std::rc::Rc<RefCell<BTreeMap<Vec<u8>, Vec<u8>>>> = Default::default();
+    {
+        let cache = cache.clone();
+        mock::mock_cache_get(move |key| cache.borrow().get(key).cloned());
+    }
+    {
+        let cache = cache.clone();
+        mock::mock_cache_set(move |key, value| {
+            cache.borrow_mut().insert(key.to_vec(), value.to_vec());
+            Ok(())
+        });
+    }
+    mock::mock_cache_remove(move |key| cache.borrow_mut().remove(key));
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    crate::set_sinks(vec![Box::new(PinkSink), Box::new(CaptureSink)]);
+    NEXT_CORRELATION_ID.with(|next| *next.borrow_mut() = 0);
+    crate::correlation::reset();
+    crate::rate::reset();
+}
+
+/// Records logged since [`install`] or the last [`clear_captured_logs`].
+pub fn captured_logs() -> Vec<CapturedLog> {
+    CAPTURED.with(|captured| captured.borrow().clone().unwrap_or_default())
+}
+
+pub fn clear_captured_logs() {
+    CAPTURED.with(|captured| {
+        if let Some(logs) = captured.borrow_mut().as_mut() {
+            logs.clear();
+        }
+    });
+}
+
+pub(crate) fn installed() -> bool {
+    CAPTURED.with(|captured| captured.borrow().is_some())
+}
+
+/// Keeps the records for [`captured_logs`].
+pub struct CaptureSink;
+
+impl Sink for CaptureSink {
+    fn log(&self, record: &Record) {
+        CAPTURED.with(|captured| {
+            if let Some(logs) = captured.borrow_mut().as_mut() {
+                logs.push(CapturedLog {
+                    level: record.level,
+                    target: record.target.into(),
+                    tags: record.tags.to_vec(),
+                    fields: record.fields.to_vec(),
+                    correlation_id: record.correlation_id.map(Into::into),
+                    message: record.message.into(),
+                    line: record.line.into(),
+                });
+            }
+        });
+    }
+}
+
+pub(crate) fn next_correlation_id() -> String {
+    NEXT_CORRELATION_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        *next += 1;
+        format!("{:016x}", *next)
+    })
+}
+
+/// A request received by the collector mocked with [`mock_collector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectedRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl CollectedRequest {
+    pub fn body_str(&self) -> &str {
+        std::str::from_utf8(&self.body).unwrap_or_default()
+    }
+}
+
+/// Mock HTTP requests with a log collector answering `status_code` to every request, e.g. to
+/// test an [`crate::HttpSink`]. The requests are returned by [`collected_requests`].
+pub fn mock_collector(status_code: u16) {
+    COLLECTED.with(|collected| collected.borrow_mut().clear());
+    mock::mock_http_request(move |request| {
+        COLLECTED.with(|collected| {
+            collected.borrow_mut().push(CollectedRequest {
+                url: request.url,
+                method: request.method,
+                headers: request.headers,
+                body: request.body,
+            })
+        });
+        pink::chain_extension::HttpResponse {
+            status_code,
+            reason_phrase: Default::default(),
+            headers: Default::default(),
+            body: Default::default(),
+        }
+    });
+}
+
+/// Requests received by the collector since [`mock_collector`].
+pub fn collected_requests() -> Vec<CollectedRequest> {
+    COLLECTED.with(|collected| collected.borrow().clone())
+}
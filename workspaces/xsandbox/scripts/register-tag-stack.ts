@@ -0,0 +1,80 @@
+import { ContractType, DevPhase, RuntimeContext, TxHandler, waitFor } from '@devphase/service';
+
+
+export const TAG_STACK_DRIVER = 'TagStack';
+
+/**
+ * Deploys `tag_stack` contract and registers it as `TagStack` driver in the cluster,
+ * so `logging::enter_span` calls of other contracts get an actual tag stack.
+ */
+export async function registerTagStack (
+    devPhase : DevPhase
+) : Promise<string>
+{
+    const suAccount = devPhase.suAccount;
+    const cert = devPhase.suAccountCert;
+    
+    const factory = await devPhase.getFactory('tag_stack', {
+        contractType: ContractType.InkCode,
+    });
+    
+    await factory.deploy({
+        autoDeposit: true,
+        asAccount: suAccount,
+    });
+    
+    const contract = await factory.instantiate(
+        'default',
+        [],
+        { asAccount: suAccount }
+    );
+    const contractId = contract.address.toHex();
+    
+    // set driver
+    const systemContract = await devPhase.getSystemContract();
+    
+    const { gasRequired, storageDeposit } = await systemContract.query['system::setDriver'](
+        suAccount.address,
+        { cert },
+        TAG_STACK_DRIVER,
+        contractId
+    );
+    
+    await TxHandler.handle(
+        systemContract.tx['system::setDriver'](
+            {
+                gasLimit: gasRequired,
+                storageDepositLimit: storageDeposit.isCharge ? storageDeposit.asCharge : null
+            },
+            TAG_STACK_DRIVER,
+            contractId
+        ),
+        suAccount,
+        true
+    );
+    
+    await waitFor(async() => {
+        const { output } = await systemContract.query['system::getDriver'](
+            suAccount.address,
+            { cert },
+            TAG_STACK_DRIVER
+        );
+        
+        return !output.isEmpty && output?.asOk.isSome && output?.asOk.unwrap().eq(contractId);
+    }, devPhase.waitTime);
+    
+    return contractId;
+}
+
+
+export default async function(
+    runtimeContext : RuntimeContext,
+    devPhase : DevPhase
+) {
+    const contractId = await registerTagStack(devPhase);
+    
+    console.log(
+        'TagStack driver:',
+        contractId
+    );
+}
@@ -0,0 +1,124 @@
+import { registerTagStack, TAG_STACK_DRIVER } from '@/scripts/register-tag-stack';
+import { Contract, ContractType, TxHandler, waitFor } from '@devphase/service';
+import * as PhalaSdk from '@phala/sdk';
+import type { KeyringPair } from '@polkadot/keyring/types';
+
+
+describe('TagStack', () => {
+    let contractId : string;
+    let contract : Contract;
+    let signer : KeyringPair;
+    let cert : PhalaSdk.CertificateData;
+
+    let alice : KeyringPair;
+    let aliceCert : PhalaSdk.CertificateData;
+    let bob : KeyringPair;
+    let bobCert : PhalaSdk.CertificateData;
+
+    const tags = async function(account : KeyringPair, accountCert : PhalaSdk.CertificateData) {
+        const { output } = await contract.query['tagStack::tags'](account.address, { cert: accountCert });
+        return output.toJSON();
+    };
+
+    const send = async function(account : KeyringPair, message : string, ...args : any[]) {
+        await TxHandler.handle(
+            contract.tx[message]({ gasLimit: 10e12 }, ...args),
+            account,
+            true
+        );
+    };
+
+    before(async function() {
+        signer = this.devPhase.suAccount;
+        cert = this.devPhase.suAccountCert;
+
+        alice = this.devPhase.accounts.alice;
+        aliceCert = await PhalaSdk.signCertificate({ pair: alice });
+        bob = this.devPhase.accounts.bob;
+        bobCert = await PhalaSdk.signCertificate({ pair: bob });
+
+        for (const account of [ alice, bob ]) {
+            await TxHandler.handle(
+                this.api.tx
+                    .phalaPhatContracts.transferToCluster(
+                        100e12,
+                        this.devPhase.mainClusterId,
+                        account.address
+                    ),
+                account,
+                true
+            );
+        }
+
+        contractId = await registerTagStack(this.devPhase);
+
+        const factory = await this.devPhase.getFactory(
+            'tag_stack',
+            { contractType: ContractType.InkCode }
+        );
+        contract = await factory.attach(contractId);
+    });
+
+    it('Should be registered as driver', async function() {
+        const systemContract = await this.devPhase.getSystemContract();
+
+        const { output } = await systemContract.query['system::getDriver'](
+            signer.address,
+            { cert },
+            TAG_STACK_DRIVER
+        );
+        expect(output.asOk.unwrap().toHex()).to.be.eql(contractId);
+    });
+
+    it('Should keep a separate stack per caller', async function() {
+        await send(alice, 'tagStack::pushTag', 'alice-outer');
+        await send(alice, 'tagStack::pushTag', 'alice-inner');
+        await send(bob, 'tagStack::pushTag', 'bob');
+
+        await waitFor(async() => {
+            const output : any = await tags(bob, bobCert);
+            return output.ok?.length === 1;
+        }, this.devPhase.waitTime);
+
+        expect(await tags(alice, aliceCert)).to.be.eql({ ok: [ 'alice-outer', 'alice-inner' ] });
+        expect(await tags(bob, bobCert)).to.be.eql({ ok: [ 'bob' ] });
+
+        await send(alice, 'tagStack::popTag');
+
+        await waitFor(async() => {
+            const output : any = await tags(alice, aliceCert);
+            return output.ok?.length === 1;
+        }, this.devPhase.waitTime);
+
+        expect(await tags(alice, aliceCert)).to.be.eql({ ok: [ 'alice-outer' ] });
+        expect(await tags(bob, bobCert)).to.be.eql({ ok: [ 'bob' ] });
+
+        await send(alice, 'tagStack::popTag');
+        await send(bob, 'tagStack::popTag');
+
+        await waitFor(async() => {
+            const output : any = await tags(bob, bobCert);
+            return output.ok?.length === 0;
+        }, this.devPhase.waitTime);
+
+        expect(await tags(alice, aliceCert)).to.be.eql({ ok: [] });
+    });
+
+    it('Should tag the records of a contract entering spans', async function() {
+        const spanTestFactory = await this.devPhase.getFactory(
+            'span_test',
+            { contractType: ContractType.InkCode }
+        );
+        await spanTestFactory.deploy({ asAccount: alice });
+        const spanTest = await spanTestFactory.instantiate('default', [], { asAccount: alice });
+
+        const { output } = await spanTest.query['taggedRecords'](alice.address, { cert: aliceCert });
+        expect(output.toJSON()).to.be.eql({
+            ok: [
+                [ [ 'outer' ], 'first' ],
+                [ [ 'outer', 'inner' ], 'second' ],
+                [ [], 'untagged' ],
+            ]
+        });
+    });
+});